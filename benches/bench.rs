@@ -95,10 +95,15 @@ pub fn bench_encode_simd(c: &mut Criterion) {
         let n = 1 << power;
 
         #[cfg(any(
-            all(target_arch = "aarch64", feature = "aarch64-simd"),
+            all(
+                target_arch = "aarch64",
+                feature = "aarch64-simd",
+                not(feature = "safe-only")
+            ),
             all(
                 any(target_arch = "x86", target_arch = "x86_64"),
-                target_feature = "ssse3"
+                target_feature = "ssse3",
+                not(feature = "safe-only")
             )
         ))]
         for (bitname, input) in [("8bit", random_8bit(n)), ("any-bit", random_any_bit(n))] {
@@ -126,10 +131,15 @@ pub fn bench_zigzag_encode_simd(c: &mut Criterion) {
         let n = 1 << power;
 
         #[cfg(any(
-            all(target_arch = "aarch64", feature = "aarch64-simd"),
+            all(
+                target_arch = "aarch64",
+                feature = "aarch64-simd",
+                not(feature = "safe-only")
+            ),
             all(
                 any(target_arch = "x86", target_arch = "x86_64"),
-                target_feature = "ssse3"
+                target_feature = "ssse3",
+                not(feature = "safe-only")
             )
         ))]
         for (bitname, input) in [("8bit", random_8bit(n)), ("any-bit", random_any_bit(n))] {
@@ -175,10 +185,15 @@ pub fn bench_decode_scalar(c: &mut Criterion) {
 #[allow(unused_variables)]
 pub fn bench_decode_simd(c: &mut Criterion) {
     #[cfg(any(
-        all(target_arch = "aarch64", feature = "aarch64-simd"),
+        all(
+            target_arch = "aarch64",
+            feature = "aarch64-simd",
+            not(feature = "safe-only")
+        ),
         all(
             any(target_arch = "x86", target_arch = "x86_64"),
-            target_feature = "ssse3"
+            target_feature = "ssse3",
+            not(feature = "safe-only")
         )
     ))]
     {
@@ -195,7 +210,7 @@ pub fn bench_decode_simd(c: &mut Criterion) {
                     &encoded,
                     |b, encoded| {
                         b.iter(|| {
-                            let _ = streamvb::simd::decode_into(len, encoded, &mut output).unwrap();
+                            streamvb::simd::decode_into(len, encoded, &mut output).unwrap();
                             output.clear();
                         })
                     },
@@ -209,10 +224,15 @@ pub fn bench_decode_simd(c: &mut Criterion) {
 #[allow(unused_variables)]
 pub fn bench_zigzag_decode_simd(c: &mut Criterion) {
     #[cfg(any(
-        all(target_arch = "aarch64", feature = "aarch64-simd"),
+        all(
+            target_arch = "aarch64",
+            feature = "aarch64-simd",
+            not(feature = "safe-only")
+        ),
         all(
             any(target_arch = "x86", target_arch = "x86_64"),
-            target_feature = "ssse3"
+            target_feature = "ssse3",
+            not(feature = "safe-only")
         )
     ))]
     {
@@ -229,8 +249,7 @@ pub fn bench_zigzag_decode_simd(c: &mut Criterion) {
                     &encoded,
                     |b, encoded| {
                         b.iter(|| {
-                            let _ = streamvb::simd::zigzag_decode_into(len, encoded, &mut output)
-                                .unwrap();
+                            streamvb::simd::zigzag_decode_into(len, encoded, &mut output).unwrap();
                             output.clear();
                         })
                     },
@@ -241,6 +260,28 @@ pub fn bench_zigzag_decode_simd(c: &mut Criterion) {
     }
 }
 
+pub fn bench_decode_varint(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode_varint");
+    for power in 10..15 {
+        let n = 1 << power;
+
+        for (bitname, input) in [("8bit", random_8bit(n)), ("any-bit", random_any_bit(n))] {
+            group.throughput(Throughput::Elements(n as u64));
+            let (len, encoded) = streamvb::varint::encode(&input);
+            group.bench_with_input(
+                format!("{}/n={}k", bitname, n / 1024),
+                &encoded,
+                |b, encoded| {
+                    b.iter(|| {
+                        let _ = streamvb::varint::decode(len, encoded);
+                    })
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
 pub fn criterion_benchmark(c: &mut Criterion) {
     let input_8bit = random_8bit(8192);
     let input_16bit: Vec<u32> = random_16bit(8192);
@@ -329,5 +370,6 @@ criterion_group!(
     bench_decode_scalar,
     bench_decode_simd,
     bench_zigzag_decode_simd,
+    bench_decode_varint,
 );
 criterion_main!(benches);
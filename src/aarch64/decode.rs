@@ -1,158 +1,347 @@
+use std::arch::aarch64::{uint32x4_t, vld1q_u8, vqtbl1q_u8, vreinterpretq_u32_u8, vst1q_u32};
+
 use crate::{
-    common::{control_bytes_len, StreamVbyteError},
-    tables::{len::LENGTH_TABLE, shuffle::DECODE_SHUFFLE_TABLE},
+    common::{
+        control_bytes_len, DeltaDecode, FrameOfReferenceDecode, ScalarDecoder, StreamVbyteError,
+    },
+    tables::{
+        len::LENGTH_TABLE,
+        shuffle::DECODE_SHUFFLE_TABLE,
+        zero124::{ZERO124_LENGTH_TABLE, ZERO124_SHUFFLE_TABLE},
+    },
 };
 
-pub fn decode_simd(len: usize, input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
+pub(crate) use crate::common::{NoDecode, ZigZagDecode};
+
+// Adds the vectorised version of a transform. `simd_decode_4x32` sees the
+// values in order, and `decode_1` the values after them.
+pub(crate) trait Decoder: ScalarDecoder {
+    unsafe fn simd_decode_4x32(&mut self, data: uint32x4_t) -> uint32x4_t;
+}
+
+impl Decoder for NoDecode {
+    #[inline]
+    unsafe fn simd_decode_4x32(&mut self, data: uint32x4_t) -> uint32x4_t {
+        data
+    }
+}
+
+impl Decoder for ZigZagDecode {
+    #[inline]
+    unsafe fn simd_decode_4x32(&mut self, data: uint32x4_t) -> uint32x4_t {
+        use std::arch::aarch64::{vandq_u32, vdupq_n_u32, veorq_u32, vshrq_n_u32, vsubq_u32};
+
+        let data_shr_1 = vshrq_n_u32::<1>(data);
+        let zero_or_one = vandq_u32(data, vdupq_n_u32(1));
+        let mask = vsubq_u32(vdupq_n_u32(0), zero_or_one);
+        veorq_u32(data_shr_1, mask)
+    }
+}
+
+impl<D: Decoder> Decoder for DeltaDecode<D> {
+    #[inline]
+    unsafe fn simd_decode_4x32(&mut self, data: uint32x4_t) -> uint32x4_t {
+        use std::arch::aarch64::{vaddq_u32, vdupq_n_u32, vextq_u32, vgetq_lane_u32};
+
+        let data = self.inner.simd_decode_4x32(data);
+        // Prefix sum within the register: add the values shifted up by one,
+        // then by two lanes.
+        let zero = vdupq_n_u32(0);
+        let data = vaddq_u32(data, vextq_u32::<3>(zero, data));
+        let data = vaddq_u32(data, vextq_u32::<2>(zero, data));
+        let data = vaddq_u32(data, vdupq_n_u32(self.prev));
+        self.prev = vgetq_lane_u32::<3>(data);
+        data
+    }
+}
+
+impl Decoder for FrameOfReferenceDecode {
+    #[inline]
+    unsafe fn simd_decode_4x32(&mut self, data: uint32x4_t) -> uint32x4_t {
+        use std::arch::aarch64::{vaddq_u32, vdupq_n_u32};
+
+        vaddq_u32(data, vdupq_n_u32(self.reference))
+    }
+}
+
+pub(crate) fn decode_simd<D: Decoder + Default>(
+    len: usize,
+    input: &[u8],
+) -> Result<Vec<u32>, StreamVbyteError> {
+    let mut output = Vec::new();
+    decode_into_simd::<D>(len, input, &mut output)?;
+    Ok(output)
+}
+
+pub(crate) fn decode_into_simd<D: Decoder + Default>(
+    len: usize,
+    input: &[u8],
+    output: &mut Vec<u32>,
+) -> Result<(), StreamVbyteError> {
     if len == 0 {
-        return Ok(Vec::new());
+        return Ok(());
     }
-    let end: *const u8 = input.as_ptr_range().end;
     let num_controls = control_bytes_len(len);
     if num_controls >= input.len() {
         return Err(StreamVbyteError::DecodeOutOfBounds);
     }
-    let mut control_ptr: *const u8 = input.as_ptr();
-    let mut data_ptr: *const u8 = unsafe { input.as_ptr().add(num_controls) };
-    let mut result: Vec<u32> = Vec::with_capacity(len);
-    let mut output_ptr: *mut u32 = result.as_mut_ptr();
-    let mut remaining_len = len;
+    let (control, data) = input.split_at(num_controls);
+    decode_parts_into_simd::<D>(len, control, data, output)
+}
 
-    // println!("output: {:?}", output_ptr);
+/// Decode `len` values from separate control and data streams and append them
+/// to `output`. `data` may extend past the bytes used by the `len` values.
+pub(crate) fn decode_parts_into_simd<D: Decoder + Default>(
+    len: usize,
+    control: &[u8],
+    data: &[u8],
+    output: &mut Vec<u32>,
+) -> Result<(), StreamVbyteError> {
+    decode_parts_into_with(&mut D::default(), len, control, data, output)
+}
 
-    // The SIMD version reads data 16 bytes at once. At least the first 4 bytes
-    // correspond to the current control byte (if it is a full control byte).
-    // Bu the remaining 12 bytes may not be part of it. To ensure that it never
-    // reads past the end of the data stream we must ensure there are always at
-    // least 3 full control bytes + 1 potentially partial control byte.
-    if num_controls > 4 {
-        unsafe {
-            let num_controls = num_controls - 4;
-            let (new_data_ptr, ok) = decode_neon_worker_checked_unrolled(
-                control_ptr,
-                data_ptr,
-                end,
-                output_ptr,
-                num_controls,
-            );
-            if !ok {
-                return Err(StreamVbyteError::DecodeOutOfBounds);
-            }
-            data_ptr = new_data_ptr;
-            control_ptr = control_ptr.add(num_controls);
-            output_ptr = output_ptr.add(4 * num_controls);
-            remaining_len -= 4 * num_controls;
-        }
+/// Like [decode_parts_into_simd], but transforms the values with `decoder`.
+pub(crate) fn decode_parts_into_with<D: Decoder>(
+    decoder: &mut D,
+    len: usize,
+    control: &[u8],
+    data: &[u8],
+    output: &mut Vec<u32>,
+) -> Result<(), StreamVbyteError> {
+    if len == 0 {
+        return Ok(());
     }
-    // Decode the leftovers using scalar decoder.
+    // Checked before reserving, so a bogus `len` cannot trigger a huge
+    // allocation.
+    if control.len() < control_bytes_len(len) {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    output.reserve(len);
     unsafe {
-        let (_, ok) = crate::scalar::decode::decode_unroll_inner_checked(
+        let output_ptr: *mut u32 = output.as_mut_ptr().add(output.len());
+        decode_parts_to_ptr_with(decoder, len, control, data, output_ptr)?;
+        output.set_len(output.len() + len);
+    }
+    Ok(())
+}
+
+/// Like [decode_parts_into_simd], but writes exactly `len` values to
+/// `output_ptr`.
+///
+/// Safety: `output_ptr` must be valid for writing `len` values.
+#[cfg(feature = "rayon")]
+pub(crate) unsafe fn decode_parts_to_ptr_simd<D: Decoder + Default>(
+    len: usize,
+    control: &[u8],
+    data: &[u8],
+    output_ptr: *mut u32,
+) -> Result<(), StreamVbyteError> {
+    decode_parts_to_ptr_with(&mut D::default(), len, control, data, output_ptr)
+}
+
+unsafe fn decode_parts_to_ptr_with<D: Decoder>(
+    decoder: &mut D,
+    len: usize,
+    control: &[u8],
+    data: &[u8],
+    mut output_ptr: *mut u32,
+) -> Result<(), StreamVbyteError> {
+    if len == 0 {
+        return Ok(());
+    }
+    let num_controls = control_bytes_len(len);
+    if control.len() < num_controls {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+
+    let end: *const u8 = data.as_ptr_range().end;
+    let mut control_ptr: *const u8 = control.as_ptr();
+    let mut data_ptr: *const u8 = data.as_ptr();
+
+    let mut remaining_len = len;
+
+    // The SIMD version reads data 16 bytes at once, no matter the value of the
+    // control byte. One control byte corresponds to between 4 and 16 input bytes.
+    // Therefore we need to read at least 4 control bytes. But the last byte
+    // might be partial, so we need > 4 control bytes.
+    if num_controls > 4 {
+        let (new_data_ptr, done) = decode_neon_worker_unrolled(
+            decoder,
             control_ptr,
             data_ptr,
             end,
             output_ptr,
-            remaining_len,
+            num_controls - 4,
         );
-        if !ok {
-            return Err(StreamVbyteError::DecodeOutOfBounds);
-        }
+        data_ptr = new_data_ptr;
+        control_ptr = control_ptr.add(done);
+        output_ptr = output_ptr.add(4 * done);
+        remaining_len -= 4 * done;
     }
-
-    unsafe { result.set_len(len) };
-
-    Ok(result)
+    // Decode the leftovers using scalar decoder.
+    let (_, ok) = crate::scalar::decode::decode_unroll_inner_checked(
+        control_ptr,
+        data_ptr,
+        end,
+        output_ptr,
+        remaining_len,
+        |x| decoder.decode_1(x),
+    );
+    if !ok {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    Ok(())
 }
 
-unsafe fn decode_neon_worker_checked_unrolled(
-    mut control_ptr: *const u8,
+// Decodes up to `num_controls` full control bytes, stopping early when fewer
+// than 16 data bytes are left to read. Returns the new data pointer and the
+// number of control bytes consumed; the caller decodes the rest.
+unsafe fn decode_neon_worker_unrolled<D: Decoder>(
+    decoder: &mut D,
+    control_ptr: *const u8,
     mut data_ptr: *const u8,
     end_ptr: *const u8,
     mut decoded_ptr: *mut u32,
-    mut num_controls: usize,
-) -> (*const u8, bool) {
-    // println!(
-    //     "n={:?}, ctrl={:?}, data={:?}, end={:?}, out={:?}",
-    //     num_controls, control_ptr, data_ptr, end_ptr, decoded_ptr,
-    // );
-    while num_controls >= 4 {
-        let control1 = *control_ptr;
-        control_ptr = control_ptr.add(1);
-        let control2 = *control_ptr;
-        control_ptr = control_ptr.add(1);
-        let control3 = *control_ptr;
-        control_ptr = control_ptr.add(1);
-        let control4 = *control_ptr;
-        control_ptr = control_ptr.add(1);
-
-        if data_ptr.add(64) > end_ptr {
-            break;
-            // return (data_ptr, false);
-        }
+    num_controls: usize,
+) -> (*const u8, usize) {
+    let mut done = 0;
+    while done + 4 <= num_controls && end_ptr.offset_from(data_ptr) >= 64 {
+        let control1 = *control_ptr.add(done);
+        let control2 = *control_ptr.add(done + 1);
+        let control3 = *control_ptr.add(done + 2);
+        let control4 = *control_ptr.add(done + 3);
+        done += 4;
 
-        num_controls -= 4;
-
-        data_ptr = step_simd(control1, data_ptr, decoded_ptr);
+        data_ptr = step_simd(decoder, control1, data_ptr, decoded_ptr);
         decoded_ptr = decoded_ptr.add(4_usize);
-        data_ptr = step_simd(control2, data_ptr, decoded_ptr);
+        data_ptr = step_simd(decoder, control2, data_ptr, decoded_ptr);
         decoded_ptr = decoded_ptr.add(4_usize);
-        data_ptr = step_simd(control3, data_ptr, decoded_ptr);
+        data_ptr = step_simd(decoder, control3, data_ptr, decoded_ptr);
         decoded_ptr = decoded_ptr.add(4_usize);
-        data_ptr = step_simd(control4, data_ptr, decoded_ptr);
+        data_ptr = step_simd(decoder, control4, data_ptr, decoded_ptr);
         decoded_ptr = decoded_ptr.add(4_usize);
     }
-    // println!("Done big steps");
-    // println!(
-    //     "n={:?}, ctrl={:?}, data={:?}, end={:?}, out={:?}",
-    //     num_controls, control_ptr, data_ptr, end_ptr, decoded_ptr,
-    // );
-    while num_controls > 0 {
-        let control = *control_ptr;
-        control_ptr = control_ptr.add(1);
-        num_controls -= 1;
-
-        if data_ptr.add(16) > end_ptr {
-            return (data_ptr, false);
-        }
-        data_ptr = step_simd(control, data_ptr, decoded_ptr);
+    while done < num_controls && end_ptr.offset_from(data_ptr) >= 16 {
+        data_ptr = step_simd(decoder, *control_ptr.add(done), data_ptr, decoded_ptr);
         decoded_ptr = decoded_ptr.add(4_usize);
+        done += 1;
     }
 
-    (data_ptr, true)
+    (data_ptr, done)
 }
 
-use multiversion::target;
+/// Like [decode_parts_into_simd], for the 0124 layout of [crate::zero124].
+/// Returns the number of data bytes used.
+pub(crate) fn decode_0124_parts_into(
+    len: usize,
+    control: &[u8],
+    data: &[u8],
+    output: &mut Vec<u32>,
+) -> Result<usize, StreamVbyteError> {
+    if control.len() < control_bytes_len(len) {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    output.reserve(len);
+    let end: *const u8 = data.as_ptr_range().end;
+    let mut data_ptr: *const u8 = data.as_ptr();
+    // Full control bytes, as long as 16 data bytes can be read. A control byte
+    // of zeros uses no data bytes at all, so this is checked every time.
+    let mut done = 0;
+    let used = unsafe {
+        let mut decoded_ptr: *mut u32 = output.as_mut_ptr().add(output.len());
+        while done < len / 4 && end.offset_from(data_ptr) >= 16 {
+            data_ptr = step_simd_with_tables(
+                &mut NoDecode,
+                &ZERO124_SHUFFLE_TABLE,
+                &ZERO124_LENGTH_TABLE,
+                control[done],
+                data_ptr,
+                decoded_ptr,
+            );
+            decoded_ptr = decoded_ptr.add(4);
+            done += 1;
+        }
+        output.set_len(output.len() + 4 * done);
+        data_ptr.offset_from(data.as_ptr()) as usize
+    };
+    let rest = crate::zero124::decode_parts_into_scalar(
+        len - 4 * done,
+        &control[done..],
+        &data[used..],
+        output,
+    )?;
+    Ok(used + rest)
+}
 
-// #[cfg(target_feature="neon")]
-#[target("aarch64+neon")]
 #[inline]
-unsafe fn step_simd(control: u8, data_ptr: *const u8, decoded_ptr: *mut u32) -> *const u8 {
-    use std::arch::aarch64::{uint8x16_t, vld1q_u8, vqtbl1q_u8, vst1q_u8};
+pub(crate) unsafe fn step_simd<D: Decoder>(
+    decoder: &mut D,
+    control: u8,
+    data_ptr: *const u8,
+    decoded_ptr: *mut u32,
+) -> *const u8 {
+    step_simd_with_tables(
+        decoder,
+        &DECODE_SHUFFLE_TABLE,
+        &LENGTH_TABLE,
+        control,
+        data_ptr,
+        decoded_ptr,
+    )
+}
 
+// Like `step_simd`, for a layout with other data lengths per 2-bit code.
+#[inline]
+unsafe fn step_simd_with_tables<D: Decoder>(
+    decoder: &mut D,
+    shuffle_table: &[[u8; 16]; 256],
+    length_table: &[u8; 256],
+    control: u8,
+    data_ptr: *const u8,
+    decoded_ptr: *mut u32,
+) -> *const u8 {
     // Safety: Safe if source data has 12 extra bytes allocated (we always
     // consume at least 4 bytes).
-    let encoded: uint8x16_t = vld1q_u8(data_ptr);
-    let entry: *const [u8; 16] = &DECODE_SHUFFLE_TABLE[control as usize] as *const _;
-    // Safety: the types are compatible and we allow unaligned reads.
-    let mask = vld1q_u8(entry as *const u8);
-    let decoded = vqtbl1q_u8(encoded, mask);
-    let bytes_consumed: u8 = LENGTH_TABLE[control as usize];
+    let encoded = vld1q_u8(data_ptr);
+    let mask = vld1q_u8(shuffle_table[control as usize].as_ptr());
+    // Out-of-range indices (0xff) in the mask produce zero bytes, just like
+    // the high bit does for `pshufb`.
+    let decoded = decoder.simd_decode_4x32(vreinterpretq_u32_u8(vqtbl1q_u8(encoded, mask)));
+    let bytes_consumed: u8 = length_table[control as usize];
     let data_ptr = data_ptr.add(bytes_consumed as usize);
     // Safety: we allocated enough memory.
-    vst1q_u8(decoded_ptr as *mut u8, decoded);
+    vst1q_u32(decoded_ptr, decoded);
     data_ptr
 }
 
-#[test]
-fn test_step_simd() {
-    let control = 0b_1000_0111;
-    let data: Vec<u8> = (1..16).collect();
-    let mut out: Vec<u32> = vec![0; 4];
-    let ofs = unsafe {
-        let p = step_simd(control, data.as_ptr(), out.as_mut_ptr());
-        p.offset_from(data.as_ptr())
-    };
-    println!("{:x?}, ofs={}", out, ofs);
-    assert_eq!(out, vec![0x04030201, 0x0605, 0x07, 0x0a0908]);
-    assert_eq!(ofs, 10);
-    // assert!(false)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step() {
+        let control = 0b_1000_0111;
+        let data: Vec<u8> = (1..17).collect();
+        let mut out: Vec<u32> = vec![0; 4];
+        let ofs = unsafe {
+            let p = step_simd(&mut NoDecode, control, data.as_ptr(), out.as_mut_ptr());
+            p.offset_from(data.as_ptr())
+        };
+        assert_eq!(out, vec![0x04030201, 0x0605, 0x07, 0x0a0908]);
+        assert_eq!(ofs, 10);
+    }
+
+    #[test]
+    fn matches_scalar() {
+        for n in 0..200 {
+            let input = &crate::common::sample_values(n);
+            let (len, bytes) = crate::safe::encode(input);
+            assert_eq!(&decode_simd::<NoDecode>(len, &bytes).unwrap(), input);
+            let zigzag: Vec<u32> = input.iter().map(|&x| ZigZagDecode.decode_1(x)).collect();
+            assert_eq!(decode_simd::<ZigZagDecode>(len, &bytes).unwrap(), zigzag);
+            if len > 0 {
+                assert!(decode_simd::<NoDecode>(len, &bytes[..bytes.len() - 1]).is_err());
+            }
+        }
+    }
 }
@@ -1,35 +1,79 @@
-pub fn encode_simd(input: &[u32]) -> (usize, Vec<u8>) {
+use std::arch::aarch64::{
+    uint32x2_t, uint32x4_t, uint8x16_t, uint8x8_t, vclzq_u32, vdupq_n_u32, vld1_u32, vld1_u8,
+    vld1q_u32, vld1q_u8, vmul_u32, vqsubq_u32, vqtbl1_u8, vqtbl1q_u8, vreinterpret_u32_u8,
+    vreinterpretq_u8_u32, vshrq_n_u32, vst1_u32, vst1q_u8,
+};
+
+use crate::common::{control_bytes_len, encode_one, max_compressed_len};
+
+pub(crate) trait Encoder {
+    unsafe fn simd_encode_4x32(data: uint32x4_t) -> uint32x4_t;
+    fn encode_1(x: u32) -> u32;
+}
+
+pub(crate) struct NoEncode;
+impl Encoder for NoEncode {
+    #[inline]
+    unsafe fn simd_encode_4x32(data: uint32x4_t) -> uint32x4_t {
+        data
+    }
+
+    #[inline]
+    fn encode_1(x: u32) -> u32 {
+        x
+    }
+}
+
+pub(crate) struct ZigZagEncode;
+impl Encoder for ZigZagEncode {
+    #[inline]
+    unsafe fn simd_encode_4x32(data: uint32x4_t) -> uint32x4_t {
+        use std::arch::aarch64::{
+            veorq_u32, vreinterpretq_s32_u32, vreinterpretq_u32_s32, vshlq_n_u32, vshrq_n_s32,
+        };
+
+        let data_shl_1 = vshlq_n_u32::<1>(data);
+        let data_shr_31 = vreinterpretq_u32_s32(vshrq_n_s32::<31>(vreinterpretq_s32_u32(data)));
+        veorq_u32(data_shl_1, data_shr_31)
+    }
+
+    #[inline]
+    fn encode_1(x: u32) -> u32 {
+        let x: i32 = x as i32;
+        (x as u32).wrapping_add(x as u32) ^ ((x >> 31) as u32)
+    }
+}
+
+pub(crate) fn encode_simd<E: Encoder>(input: &[u32]) -> (usize, Vec<u8>) {
+    let mut output = Vec::new();
+    let items = encode_into_simd::<E>(input, &mut output);
+    (items, output)
+}
+
+pub(crate) fn encode_into_simd<E: Encoder>(input: &[u32], output: &mut Vec<u8>) -> usize {
     let items = input.len();
     if items == 0 {
-        return (0, Vec::new());
+        return 0;
     }
 
-    let mut output: Vec<u8> = Vec::with_capacity(max_compressed_len(items));
+    output.reserve(max_compressed_len(items));
 
     // This always points to where the currently collected control byte needs
     // to be written.
-    let controls: *mut u8 = output.as_mut_ptr();
+    let controls: *mut u8 = unsafe { output.as_mut_ptr().add(output.len()) };
     let data: *mut u8 = unsafe { controls.add(control_bytes_len(items)) };
     let input: *const u32 = input.as_ptr();
 
     unsafe {
-        let data = encode_worker(items, input, controls, data);
-        let len = data.offset_from(output.as_ptr()) as usize;
-        debug_assert!(len <= output.capacity());
-        output.set_len(len)
+        let data = encode_worker::<E>(items, input, controls, data);
+        let new_len = data.offset_from(output.as_ptr()) as usize;
+        debug_assert!(new_len <= output.capacity());
+        output.set_len(new_len)
     };
 
-    (items, output)
+    items
 }
 
-use std::arch::aarch64::{
-    uint32x2_t, uint32x4_t, uint8x16_t, uint8x8_t, vclzq_u32, vdupq_n_u32, vld1_u32, vld1_u8,
-    vld1q_u32, vld1q_u8, vmul_u32, vqsubq_u32, vqtbl1_u8, vqtbl1q_u8, vreinterpret_u32_u8,
-    vreinterpretq_u8_u32, vshrq_n_u32, vst1_u32, vst1q_u8,
-};
-
-use crate::common::{control_bytes_len, max_compressed_len};
-
 static GATHER_LO: [u8; 8] = [12, 8, 4, 0, 12, 8, 4, 0];
 
 static AGGREGATORS: [u32; 2] = [
@@ -38,18 +82,18 @@ static AGGREGATORS: [u32; 2] = [
 ];
 
 // based on https://github.com/lemire/streamvbyte/blob/master/src/streamvbyte_arm_encode.c
-unsafe fn encode_worker(
+pub(crate) unsafe fn encode_worker<E: Encoder>(
     items: usize,
     mut input: *const u32,
     mut controls: *mut u8,
     mut out: *mut u8,
 ) -> *mut u8 {
-    let gatherlo: uint8x8_t = vld1_u8(&GATHER_LO as *const u8);
-    let aggregators: uint32x2_t = vld1_u32(&AGGREGATORS as *const u32);
+    let gatherlo: uint8x8_t = vld1_u8(GATHER_LO.as_ptr());
+    let aggregators: uint32x2_t = vld1_u32(AGGREGATORS.as_ptr());
 
     let end: *const u32 = input.add(items & !3);
     while input != end {
-        let data: uint32x4_t = vld1q_u32(input);
+        let data: uint32x4_t = E::simd_encode_4x32(vld1q_u32(input));
         // Ex: [11, 3322, 77665544, aa9988]
 
         // clz = count leading zero bits
@@ -66,7 +110,7 @@ unsafe fn encode_worker(
         // [00010302, 00010302]
         let mut code_and_length: [u32; 2] = [0, 0];
         vst1_u32(
-            &mut code_and_length as *mut u32,
+            code_and_length.as_mut_ptr(),
             vmul_u32(mulshift, aggregators),
         );
         // [b42d0b02, 06060502]
@@ -74,24 +118,9 @@ unsafe fn encode_worker(
         let code: u32 = code_and_length[0] >> 24;
         let len = 4 + (code_and_length[1] >> 24);
         let databytes: uint8x16_t = vreinterpretq_u8_u32(data);
-        let shuffle: uint8x16_t =
-            vld1q_u8(ENCODE_SHUFFLE_TABLE[code as usize].as_ptr() as *const u8);
+        let shuffle: uint8x16_t = vld1q_u8(ENCODE_SHUFFLE_TABLE[code as usize].as_ptr());
         vst1q_u8(out, vqtbl1q_u8(databytes, shuffle));
 
-        // println!("{:x?}", clzbytes);
-        // println!("{:x?}", lanecodes);
-        // println!("{:x?}", lanebytes);
-        // println!("{:x?}", lobytes);
-        // println!("{:x?}", mulshift);
-        // println!("{:x?}", code_and_length);
-        // println!("{:x?}", code_and_length);
-        // println!("{:x?}", databytes);
-        // println!("{:x?}", shuffle);
-
-        // for i in 0..len {
-        //     println!("{}: {:x?}", i, *out.add(i as usize));
-        // }
-
         *controls = code as u8;
         controls = controls.add(1);
         input = input.add(4);
@@ -101,10 +130,10 @@ unsafe fn encode_worker(
         let mut key: u32 = 0;
         // handle the rest
         for i in 0..items & 3 {
-            let word = *input;
+            let word = E::encode_1(*input);
             let symbol = encode_one(word);
             key |= symbol << (i + i);
-            std::ptr::copy_nonoverlapping(input as *const u8, out, 4);
+            std::ptr::copy_nonoverlapping(word.to_le_bytes().as_ptr(), out, 4);
             input = input.add(1);
             out = out.add(symbol as usize + 1);
         }
@@ -113,13 +142,6 @@ unsafe fn encode_worker(
     out
 }
 
-fn encode_one(word: u32) -> u32 {
-    let t1 = (word > 0x000000ff) as u32;
-    let t2 = (word > 0x0000ffff) as u32;
-    let t3 = (word > 0x00ffffff) as u32;
-    t1 + t2 + t3
-}
-
 #[rustfmt::skip]
 static ENCODE_SHUFFLE_TABLE: [[u8; 16]; 256] = [
     [   0,    4,    8,   12, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 1111
@@ -431,45 +453,37 @@ fn generate_encoding_shuffle_table() {
 
 #[cfg(test)]
 mod tests {
-    #[test]
-    fn encode_step() {
-        let values = vec![
-            0x11, 0x3322, 0x77665544, 0xaa9988, 0x2010, 0x504030, 0x90000060, 0xa0, 0x70, 0x8000,
-        ];
-        let (len, encoded) = super::encode_simd(&values);
-        println!("len={}, encoded: {:x?}", len, encoded);
-    }
-
-    use rand::Rng;
+    use super::*;
 
     pub fn random_any_bit(count: usize) -> Vec<u32> {
-        let mut input: Vec<u32> = Vec::with_capacity(count);
+        use rand::Rng;
         let mut rng = rand::thread_rng();
-        for _ in 0..count {
-            let sz = rng.gen_range(1..5);
-            let b = match sz {
-                1 => rng.gen::<u8>() as u32,
-                2 => rng.gen::<u16>() as u32,
-                3 => rng.gen_range(0u32..16777216),
-                4 => rng.gen::<u32>(),
-                _ => panic!("impossible"),
-            };
-            input.push(b);
-        }
-        input
+        (0..count)
+            .map(|_| rng.gen::<u32>() >> (8 * rng.gen_range(0..4)))
+            .collect()
     }
 
     #[test]
     fn encode_random() {
         for n in 0..100 {
-            let count = 1000 + n;
-            let input = random_any_bit(count);
-
-            let (len, encoded) = super::encode_simd(&input);
-            assert_eq!(len, input.len());
+            let input = random_any_bit(1000 + n);
+            let expected = crate::safe::encode(&input);
+            assert_eq!(encode_simd::<NoEncode>(&input), expected);
 
-            let decoded = crate::scalar::decode::decode(len, &encoded).unwrap();
-            assert_eq!(&input, &decoded);
+            let zigzag: Vec<u32> = input.iter().map(|&x| ZigZagEncode::encode_1(x)).collect();
+            assert_eq!(
+                encode_simd::<ZigZagEncode>(&input),
+                crate::safe::encode(&zigzag)
+            );
         }
     }
+
+    #[test]
+    fn encode_into_appends() {
+        let input = random_any_bit(37);
+        let mut output = vec![0xaa; 5];
+        assert_eq!(encode_into_simd::<NoEncode>(&input, &mut output), 37);
+        assert_eq!(output[..5], [0xaa; 5]);
+        assert_eq!(output[5..], crate::safe::encode(&input).1[..]);
+    }
 }
@@ -1,3 +1,2 @@
-// #[cfg(target_feature="neon")]
-pub mod decode;
-pub mod encode;
+pub(crate) mod decode;
+pub(crate) mod encode;
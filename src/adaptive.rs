@@ -0,0 +1,419 @@
+//! Adaptive encoding that picks the best codec for each block of values.
+//!
+//! The input is split into blocks of [BLOCK_LEN] values. For every block the
+//! encoder computes the exact size under each [Codec], like
+//! [crate::exact_compressed_len] does, and keeps the smallest. Each block is
+//! written as
+//!
+//! ```text
+//! [codec: u8] [reference: u32 LE, frame-of-reference only] [encoded block]
+//! ```
+//!
+//! where the encoded block uses the layout of [crate::encode], or of
+//! [crate::zero124::encode] for [Codec::Zero124]. Delta coding continues from
+//! the last value of the previous block, so sorted columns stay cheap across
+//! block boundaries.
+//!
+//! Decoding uses the same SIMD kernels as [crate::decode], and those of
+//! [crate::zero124] for 0124 blocks. Where the platform supports it, the
+//! zigzag, delta and frame-of-reference steps are fused into the kernel, so
+//! every value is written once.
+use crate::common::{
+    control_bytes_len, data_bytes_len, encode_one, exact_compressed_len,
+    exact_compressed_len_delta, exact_compressed_len_for, exact_compressed_len_zigzag,
+    zigzag_encode_1, DeltaDecode, FrameOfReferenceDecode, NoDecode, StreamVbyteError, ZigZagDecode,
+};
+
+#[cfg(all(
+    target_arch = "aarch64",
+    feature = "aarch64-simd",
+    not(feature = "safe-only")
+))]
+use crate::aarch64::decode as kernels;
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "ssse3",
+    not(feature = "safe-only")
+))]
+use crate::x86_64::decode as kernels;
+
+/// Number of values in a full block.
+pub const BLOCK_LEN: usize = 256;
+
+/// How a block is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Codec {
+    /// The values as they are, like [crate::encode].
+    Plain,
+    /// Zigzag encoded values, for signed values stored as `u32`.
+    ZigZag,
+    /// Differences to the previous value, for sorted values.
+    Delta,
+    /// Zigzag encoded differences, for values that go up and down slowly.
+    DeltaZigZag,
+    /// The values as they are, in the layout of [crate::zero124], for values
+    /// that are mostly zero.
+    Zero124,
+    /// Differences to the minimum of the block, which is stored in the block
+    /// header, for values within a small range.
+    FrameOfReference,
+}
+
+impl Codec {
+    /// All codecs, in the order of preference when sizes are equal.
+    pub const ALL: [Codec; 6] = [
+        Codec::Plain,
+        Codec::ZigZag,
+        Codec::Delta,
+        Codec::DeltaZigZag,
+        Codec::Zero124,
+        Codec::FrameOfReference,
+    ];
+
+    fn from_u8(byte: u8) -> Option<Codec> {
+        Codec::ALL.get(byte as usize).copied()
+    }
+}
+
+/// Encode a slice of `u32` values, choosing a codec per block.
+///
+/// ```
+/// let sorted: Vec<u32> = (0..1000).map(|x| 1_000_000 + 3 * x).collect();
+/// let (len, bytes) = streamvb::adaptive::encode(&sorted);
+/// assert!(bytes.len() < streamvb::encode(&sorted).1.len() / 2);
+/// assert_eq!(streamvb::adaptive::decode(len, &bytes).unwrap(), sorted);
+/// ```
+pub fn encode(values: &[u32]) -> (usize, Vec<u8>) {
+    let mut output = Vec::new();
+    let mut scratch = Vec::with_capacity(BLOCK_LEN);
+    let mut prev = 0;
+    for block in values.chunks(BLOCK_LEN) {
+        let (codec, reference) = choose(block, prev);
+        output.push(codec as u8);
+        scratch.clear();
+        match codec {
+            Codec::Plain => crate::encode_into(block, &mut output),
+            Codec::ZigZag => {
+                scratch.extend(block.iter().map(|&x| zigzag_encode_1(x)));
+                crate::encode_into(&scratch, &mut output);
+            }
+            Codec::Delta => {
+                scratch.extend(differences(block, prev));
+                crate::encode_into(&scratch, &mut output);
+            }
+            Codec::DeltaZigZag => {
+                scratch.extend(differences(block, prev).map(zigzag_encode_1));
+                crate::encode_into(&scratch, &mut output);
+            }
+            Codec::Zero124 => crate::zero124::encode_into(block, &mut output),
+            Codec::FrameOfReference => {
+                output.extend_from_slice(&reference.to_le_bytes());
+                scratch.extend(block.iter().map(|&x| x.wrapping_sub(reference)));
+                crate::encode_into(&scratch, &mut output);
+            }
+        }
+        prev = block[block.len() - 1];
+    }
+    (values.len(), output)
+}
+
+/// Decode `len` values encoded using [encode].
+///
+/// Returns an error if the decoding process tried to read bytes outside of the
+/// input slice or a block header is invalid.
+pub fn decode(len: usize, input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
+    // Every value takes at least a quarter control byte. Checked before
+    // reserving, so a bogus `len` cannot trigger a huge allocation.
+    if control_bytes_len(len) > input.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    let mut output = Vec::with_capacity(len);
+    let mut input = input;
+    let mut prev = 0;
+    while output.len() < len {
+        let n = (len - output.len()).min(BLOCK_LEN);
+        let (codec, rest) = split_header(input)?;
+        let used = decode_block(codec, n, rest, prev, &mut output)?;
+        input = &rest[used..];
+        prev = output[output.len() - 1];
+    }
+    Ok(output)
+}
+
+/// The codec chosen for each block of `len` values encoded using [encode].
+///
+/// ```
+/// use streamvb::adaptive::{self, Codec};
+/// let (len, bytes) = adaptive::encode(&[0; 300]);
+/// assert_eq!(adaptive::block_codecs(len, &bytes).unwrap(), [Codec::Zero124; 2]);
+/// ```
+pub fn block_codecs(len: usize, input: &[u8]) -> Result<Vec<Codec>, StreamVbyteError> {
+    if control_bytes_len(len) > input.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    let mut codecs = Vec::with_capacity(len.div_ceil(BLOCK_LEN));
+    let mut input = input;
+    let mut remaining = len;
+    while remaining > 0 {
+        let n = remaining.min(BLOCK_LEN);
+        let (codec, rest) = split_header(input)?;
+        input = &rest[block_len(codec, n, rest)?..];
+        codecs.push(codec);
+        remaining -= n;
+    }
+    Ok(codecs)
+}
+
+// Returns the smallest codec for `block`, and the reference for
+// frame-of-reference coding. `prev` is the last value of the previous block.
+fn choose(block: &[u32], prev: u32) -> (Codec, u32) {
+    let reference = *block.iter().min().unwrap();
+    // `exact_compressed_len_delta` starts from 0, fix up the first value.
+    let first = block[0];
+    let delta = exact_compressed_len_delta(block) - encode_one(first) as usize
+        + encode_one(first.wrapping_sub(prev)) as usize;
+    // Data bytes only, the control bytes are the same for every codec.
+    let sizes = [
+        exact_compressed_len(block),
+        exact_compressed_len_zigzag(block),
+        delta,
+        differences(block, prev)
+            .map(|x| encode_one(zigzag_encode_1(x)) as usize + 1)
+            .sum(),
+        block
+            .iter()
+            .map(|&x| match x {
+                0 => 0,
+                1..=0xff => 1,
+                0x100..=0xffff => 2,
+                _ => 4,
+            })
+            .sum(),
+        4 + exact_compressed_len_for(block, reference),
+    ];
+    // The first of several equal sizes wins.
+    let mut best = 0;
+    for (i, &size) in sizes.iter().enumerate() {
+        if size < sizes[best] {
+            best = i;
+        }
+    }
+    (Codec::ALL[best], reference)
+}
+
+fn differences(block: &[u32], mut prev: u32) -> impl Iterator<Item = u32> + '_ {
+    block.iter().map(move |&x| {
+        let delta = x.wrapping_sub(prev);
+        prev = x;
+        delta
+    })
+}
+
+fn split_header(input: &[u8]) -> Result<(Codec, &[u8]), StreamVbyteError> {
+    let (&header, rest) = input
+        .split_first()
+        .ok_or(StreamVbyteError::DecodeOutOfBounds)?;
+    let codec = Codec::from_u8(header).ok_or(StreamVbyteError::InvalidBlockHeader)?;
+    Ok((codec, rest))
+}
+
+// Returns the number of bytes used by a block of `n` values after its codec
+// byte, without decoding it.
+fn block_len(codec: Codec, n: usize, input: &[u8]) -> Result<usize, StreamVbyteError> {
+    match codec {
+        Codec::Zero124 => crate::zero124::encoded_len(n, input),
+        Codec::FrameOfReference => {
+            let parts = input.get(4..).ok_or(StreamVbyteError::DecodeOutOfBounds)?;
+            Ok(4 + parts_len(n, parts)?)
+        }
+        _ => parts_len(n, input),
+    }
+}
+
+// Like `block_len` for `n` values in the layout of [crate::encode].
+fn parts_len(n: usize, input: &[u8]) -> Result<usize, StreamVbyteError> {
+    let num_controls = control_bytes_len(n);
+    if num_controls > input.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    let (control, data) = input.split_at(num_controls);
+    let data_len = data_bytes_len(control, n);
+    if data_len > data.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    Ok(num_controls + data_len)
+}
+
+// Decodes a block of `n` values after its codec byte, appends them to
+// `output` and returns the number of bytes used. `prev` is the last value of
+// the previous block.
+fn decode_block(
+    codec: Codec,
+    n: usize,
+    input: &[u8],
+    prev: u32,
+    output: &mut Vec<u32>,
+) -> Result<usize, StreamVbyteError> {
+    match codec {
+        Codec::Plain => decode_parts(n, input, output, &mut NoDecode),
+        Codec::ZigZag => decode_parts(n, input, output, &mut ZigZagDecode),
+        Codec::Delta => decode_parts(n, input, output, &mut DeltaDecode::new(NoDecode, prev)),
+        Codec::DeltaZigZag => {
+            decode_parts(n, input, output, &mut DeltaDecode::new(ZigZagDecode, prev))
+        }
+        Codec::Zero124 => crate::zero124::decode_into(n, input, output),
+        Codec::FrameOfReference => {
+            if input.len() < 4 {
+                return Err(StreamVbyteError::DecodeOutOfBounds);
+            }
+            let reference = u32::from_le_bytes([input[0], input[1], input[2], input[3]]);
+            let mut decoder = FrameOfReferenceDecode::new(reference);
+            Ok(4 + decode_parts(n, &input[4..], output, &mut decoder)?)
+        }
+    }
+}
+
+// Decodes `n` values in the layout of [crate::encode], transforming them with
+// `decoder`, and returns the number of bytes used. The data is passed on with
+// everything after it, so that the SIMD kernels do not have to fall back to
+// scalar code for the last values.
+fn decode_parts<D: kernels::Decoder>(
+    n: usize,
+    input: &[u8],
+    output: &mut Vec<u32>,
+    decoder: &mut D,
+) -> Result<usize, StreamVbyteError> {
+    let num_controls = control_bytes_len(n);
+    if num_controls > input.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    let (control, data) = input.split_at(num_controls);
+    kernels::decode_parts_into_with(decoder, n, control, data, output)?;
+    // Decoding succeeded, so the data is in bounds.
+    Ok(num_controls + data_bytes_len(control, n))
+}
+
+// Without SIMD, the values are decoded first and then transformed in a
+// second pass.
+#[cfg(not(any(
+    all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    ),
+    all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    )
+)))]
+mod kernels {
+    pub(super) use crate::common::ScalarDecoder as Decoder;
+    use crate::common::StreamVbyteError;
+
+    pub(super) fn decode_parts_into_with<D: Decoder>(
+        decoder: &mut D,
+        len: usize,
+        control: &[u8],
+        data: &[u8],
+        output: &mut Vec<u32>,
+    ) -> Result<(), StreamVbyteError> {
+        let start = output.len();
+        crate::decode_parts_into(len, control, data, output)?;
+        for value in &mut output[start..] {
+            *value = decoder.decode_1(*value);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::sample_values;
+
+    // Random high bits, so that the values are spread over the whole range.
+    fn noise(n: usize) -> impl Iterator<Item = u32> {
+        sample_values(n).into_iter().map(u32::reverse_bits)
+    }
+
+    #[test]
+    fn chooses_codec() {
+        let n = 2 * BLOCK_LEN;
+        let columns: Vec<(Codec, Vec<u32>)> = vec![
+            (Codec::Plain, sample_values(n)),
+            (
+                Codec::ZigZag,
+                noise(n).map(|x| (x as i32 >> 24) as u32).collect(),
+            ),
+            (
+                Codec::Delta,
+                (0..n as u32).map(|x| (1 << 30) + x * 1000).collect(),
+            ),
+            (
+                Codec::DeltaZigZag,
+                (0..n as u32).map(|x| u32::MAX - x * 100).collect(),
+            ),
+            (
+                Codec::Zero124,
+                (0..n as u32)
+                    .map(|x| (x % 16 == 0) as u32 * 70000)
+                    .collect(),
+            ),
+            (
+                Codec::FrameOfReference,
+                noise(n).map(|x| (1 << 30) + (x >> 24)).collect(),
+            ),
+        ];
+        for (codec, values) in columns {
+            let (len, bytes) = encode(&values);
+            assert_eq!(
+                block_codecs(len, &bytes).unwrap(),
+                [codec; 2],
+                "{:?}",
+                codec
+            );
+            assert_eq!(decode(len, &bytes).unwrap(), values, "{:?}", codec);
+        }
+    }
+
+    #[test]
+    fn mixed_blocks() {
+        // Every block of a different kind, and a short last block.
+        let mut values = sample_values(BLOCK_LEN);
+        let last = values[BLOCK_LEN - 1];
+        values.extend((0..BLOCK_LEN as u32).map(|x| last.wrapping_add(x)));
+        values.extend(noise(BLOCK_LEN).map(|x| (x as i32 >> 24) as u32));
+        values.extend([0; BLOCK_LEN]);
+        values.extend(noise(100).map(|x| 5_000_000 + (x >> 24)));
+        let (len, bytes) = encode(&values);
+        assert_eq!(
+            block_codecs(len, &bytes).unwrap(),
+            [
+                Codec::Plain,
+                Codec::Delta,
+                Codec::ZigZag,
+                Codec::Zero124,
+                Codec::FrameOfReference
+            ]
+        );
+        assert_eq!(decode(len, &bytes).unwrap(), values);
+        for end in 0..bytes.len() {
+            assert!(decode(len, &bytes[..end]).is_err());
+        }
+        assert!(decode(len + 1, &bytes).is_err());
+        assert!(decode(usize::MAX, &bytes).is_err());
+    }
+
+    #[test]
+    fn bad_header() {
+        let (len, mut bytes) = encode(&[1, 2, 3]);
+        bytes[0] = Codec::ALL.len() as u8;
+        assert!(matches!(
+            decode(len, &bytes),
+            Err(StreamVbyteError::InvalidBlockHeader)
+        ));
+        assert_eq!(encode(&[]), (0, vec![]));
+        assert_eq!(decode(0, &[]).unwrap(), Vec::<u32>::new());
+    }
+}
@@ -0,0 +1,248 @@
+//! Explicit selection of the implementation, e.g. for benchmarking or to
+//! reproduce a bug on a particular code path.
+//!
+//! The functions in the crate root pick an implementation at compile time.
+//! [Backend] instead checks at runtime whether the host supports it, so the
+//! SSSE3 code can be used even if the crate was not compiled with
+//! `-C target-feature=+ssse3`. All backends produce the same bytes.
+use crate::common::StreamVbyteError;
+
+/// An implementation of the codec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// Portable code using raw pointers, the default without SIMD.
+    Scalar,
+    /// Portable code without `unsafe`, see [crate::safe].
+    Safe,
+    /// x86 and x86-64 with SSSE3.
+    Ssse3,
+    /// Reserved for x86-64 with AVX2. There are no AVX2 kernels yet, so this
+    /// is never available: [Backend::detect] never returns it, and
+    /// [encode_with] and [decode_with] always return
+    /// [StreamVbyteError::UnsupportedBackend] for it.
+    Avx2,
+    /// AArch64 with NEON.
+    Neon,
+}
+
+impl Backend {
+    /// All backends, whether available or not.
+    pub const ALL: [Backend; 5] = [
+        Backend::Scalar,
+        Backend::Safe,
+        Backend::Ssse3,
+        Backend::Avx2,
+        Backend::Neon,
+    ];
+
+    /// The fastest backend available on this host.
+    ///
+    /// ```
+    /// assert!(streamvb::Backend::detect().is_available());
+    /// ```
+    pub fn detect() -> Backend {
+        [Backend::Ssse3, Backend::Neon, Backend::Scalar]
+            .iter()
+            .copied()
+            .find(|backend| backend.is_available())
+            .unwrap_or(Backend::Safe)
+    }
+
+    /// Whether this backend was compiled in and the host supports it.
+    pub fn is_available(self) -> bool {
+        match self {
+            // With `safe-only`, the scalar functions are the safe ones.
+            Backend::Scalar => !cfg!(feature = "safe-only"),
+            Backend::Safe => true,
+            Backend::Ssse3 => ssse3_available(),
+            Backend::Avx2 => false,
+            Backend::Neon => cfg!(all(
+                target_arch = "aarch64",
+                feature = "aarch64-simd",
+                not(feature = "safe-only")
+            )),
+        }
+    }
+}
+
+/// Encode `values` like [crate::encode], using `backend`.
+///
+/// Returns [StreamVbyteError::UnsupportedBackend] if `backend` is not
+/// available.
+///
+/// ```
+/// use streamvb::{backend, Backend};
+/// let values = [1, 300, 70000];
+/// let (len, bytes) = backend::encode_with(Backend::Safe, &values).unwrap();
+/// assert_eq!(backend::decode_with(Backend::detect(), len, &bytes).unwrap(), values);
+/// ```
+pub fn encode_with(backend: Backend, values: &[u32]) -> Result<(usize, Vec<u8>), StreamVbyteError> {
+    if !backend.is_available() {
+        return Err(StreamVbyteError::UnsupportedBackend);
+    }
+    match backend {
+        Backend::Scalar => Ok(crate::scalar::encode(values)),
+        Backend::Safe => Ok(crate::safe::encode(values)),
+        Backend::Ssse3 => {
+            #[cfg(all(
+                any(target_arch = "x86", target_arch = "x86_64"),
+                target_feature = "sse2",
+                not(feature = "safe-only")
+            ))]
+            // Safety: availability of SSSE3 was checked above.
+            return Ok(unsafe { ssse3::encode(values) });
+            #[allow(unreachable_code)]
+            {
+                unreachable!("SSSE3 is not compiled in")
+            }
+        }
+        Backend::Neon => {
+            #[cfg(all(
+                target_arch = "aarch64",
+                feature = "aarch64-simd",
+                not(feature = "safe-only")
+            ))]
+            return Ok(crate::aarch64::encode::encode_simd::<
+                crate::aarch64::encode::NoEncode,
+            >(values));
+            #[allow(unreachable_code)]
+            {
+                unreachable!("NEON is not compiled in")
+            }
+        }
+        Backend::Avx2 => Err(StreamVbyteError::UnsupportedBackend),
+    }
+}
+
+/// Decode `len` values like [crate::decode], using `backend`.
+///
+/// Returns [StreamVbyteError::UnsupportedBackend] if `backend` is not
+/// available.
+pub fn decode_with(
+    backend: Backend,
+    len: usize,
+    input: &[u8],
+) -> Result<Vec<u32>, StreamVbyteError> {
+    if !backend.is_available() {
+        return Err(StreamVbyteError::UnsupportedBackend);
+    }
+    match backend {
+        Backend::Scalar => crate::scalar::decode(len, input),
+        Backend::Safe => crate::safe::decode(len, input),
+        Backend::Ssse3 => {
+            #[cfg(all(
+                any(target_arch = "x86", target_arch = "x86_64"),
+                target_feature = "sse2",
+                not(feature = "safe-only")
+            ))]
+            // Safety: availability of SSSE3 was checked above.
+            return unsafe { ssse3::decode(len, input) };
+            #[allow(unreachable_code)]
+            {
+                unreachable!("SSSE3 is not compiled in")
+            }
+        }
+        Backend::Neon => {
+            #[cfg(all(
+                target_arch = "aarch64",
+                feature = "aarch64-simd",
+                not(feature = "safe-only")
+            ))]
+            return crate::aarch64::decode::decode_simd::<crate::aarch64::decode::NoDecode>(
+                len, input,
+            );
+            #[allow(unreachable_code)]
+            {
+                unreachable!("NEON is not compiled in")
+            }
+        }
+        Backend::Avx2 => Err(StreamVbyteError::UnsupportedBackend),
+    }
+}
+
+#[allow(clippy::needless_return)]
+fn ssse3_available() -> bool {
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse2",
+        not(feature = "safe-only")
+    ))]
+    {
+        return is_x86_feature_detected!("ssse3");
+    }
+    #[allow(unreachable_code)]
+    false
+}
+
+// Entry points compiled with SSSE3 enabled, so that the kernels are inlined
+// with it even if the rest of the crate is not.
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "sse2",
+    not(feature = "safe-only")
+))]
+mod ssse3 {
+    use crate::common::StreamVbyteError;
+    use crate::x86_64::{decode::*, encode::*};
+
+    #[target_feature(enable = "ssse3")]
+    pub(super) unsafe fn encode(values: &[u32]) -> (usize, Vec<u8>) {
+        encode_simd::<NoEncode>(values)
+    }
+
+    #[target_feature(enable = "ssse3")]
+    pub(super) unsafe fn decode(len: usize, input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
+        decode_simd::<NoDecode>(len, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_with, encode_with, Backend};
+    use crate::StreamVbyteError;
+
+    #[test]
+    fn all_backends_agree() {
+        let values = crate::common::sample_values(1003);
+        let (len, expected) = crate::encode(&values);
+        for backend in Backend::ALL {
+            if !backend.is_available() {
+                assert!(matches!(
+                    encode_with(backend, &values),
+                    Err(StreamVbyteError::UnsupportedBackend)
+                ));
+                assert!(matches!(
+                    decode_with(backend, len, &expected),
+                    Err(StreamVbyteError::UnsupportedBackend)
+                ));
+                continue;
+            }
+            for n in [0, 1, 5, 16, 17, 100, 1003] {
+                let (len, bytes) = encode_with(backend, &values[..n]).unwrap();
+                assert_eq!(bytes, crate::encode(&values[..n]).1, "{:?}", backend);
+                assert_eq!(
+                    decode_with(backend, len, &bytes).unwrap(),
+                    &values[..n],
+                    "{:?}",
+                    backend
+                );
+            }
+            assert!(decode_with(backend, len, &expected[..100]).is_err());
+        }
+    }
+
+    #[test]
+    fn detect() {
+        assert!(Backend::detect().is_available());
+        assert!(Backend::Safe.is_available());
+        assert!(!Backend::Avx2.is_available());
+        #[cfg(all(
+            any(target_arch = "x86", target_arch = "x86_64"),
+            not(feature = "safe-only")
+        ))]
+        assert_eq!(
+            Backend::detect() == Backend::Ssse3,
+            is_x86_feature_detected!("ssse3")
+        );
+    }
+}
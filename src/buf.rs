@@ -0,0 +1,194 @@
+//! Encoding into [BufMut] and decoding from [Buf] of the `bytes` crate.
+//!
+//! The encoded layout is the same as for [crate::encode], so the two can be
+//! mixed freely.
+use bytes::{Buf, BufMut};
+
+use crate::common::{control_bytes_len, data_bytes_len, encode_one, StreamVbyteError};
+
+// Size of the stack buffers used when the output or input is not contiguous.
+const STAGING_LEN: usize = 256;
+
+/// Encode `values` and write control and data bytes to `buf`. Returns the
+/// number of bytes written. Decode with [decode_from_buf] or [crate::decode],
+/// passing `values.len()` as the length.
+///
+/// Values are encoded directly into `buf` if its current chunk has room for
+/// [crate::max_compressed_len] bytes, and through a small staging buffer
+/// otherwise. [BufMut] cannot reserve space, so reserve it up front, e.g. with
+/// [BytesMut::reserve][bytes::BytesMut::reserve], to get the fast path.
+///
+/// Panics if `buf` does not have enough remaining capacity, like
+/// [BufMut::put_slice].
+///
+/// ```
+/// use bytes::BytesMut;
+/// let mut buf = BytesMut::new();
+/// buf.reserve(streamvb::max_compressed_len(3));
+/// let written = streamvb::buf::encode_into_buf(&[1, 300, 70000], &mut buf);
+/// assert_eq!(written, buf.len());
+/// assert_eq!(&buf[..], &streamvb::encode(&[1, 300, 70000]).1[..]);
+/// ```
+pub fn encode_into_buf<B: BufMut>(values: &[u32], buf: &mut B) -> usize {
+    let items = values.len();
+    if items == 0 {
+        return 0;
+    }
+    // Fast path: the whole worst case fits into the current chunk.
+    #[cfg(not(feature = "safe-only"))]
+    {
+        let chunk = buf.chunk_mut();
+        if chunk.len() >= crate::max_compressed_len(items) {
+            let controls = chunk.as_mut_ptr();
+            // Safety: the chunk has room for `max_compressed_len(items)`
+            // bytes, which is the most `encode_worker` writes.
+            unsafe {
+                let data = controls.add(control_bytes_len(items));
+                let end = crate::encode_worker(values, controls, data);
+                let written = end.offset_from(controls) as usize;
+                buf.advance_mut(written);
+                return written;
+            }
+        }
+    }
+
+    // Slow path: write control bytes, then data bytes, through small staging
+    // buffers.
+    let mut staging = [0u8; STAGING_LEN];
+    let mut written = 0;
+    for block in values.chunks(4 * STAGING_LEN) {
+        let controls = block.chunks(4);
+        let n = controls.len();
+        for (key, quad) in staging.iter_mut().zip(controls) {
+            *key = quad
+                .iter()
+                .enumerate()
+                .fold(0, |key, (i, &value)| key | (encode_one(value) << (2 * i)))
+                as u8;
+        }
+        buf.put_slice(&staging[..n]);
+        written += n;
+    }
+    let mut fill = 0;
+    for &value in values {
+        if fill + 4 > STAGING_LEN {
+            buf.put_slice(&staging[..fill]);
+            written += fill;
+            fill = 0;
+        }
+        let width = encode_one(value) as usize + 1;
+        staging[fill..fill + width].copy_from_slice(&value.to_le_bytes()[..width]);
+        fill += width;
+    }
+    buf.put_slice(&staging[..fill]);
+    written + fill
+}
+
+/// Decode `len` values from `buf` and advance it by exactly the number of
+/// bytes consumed. Works with non-contiguous buffers such as [Buf::chain].
+///
+/// Returns an error if `buf` is too short. In that case the contents of a
+/// contiguous `buf` are left untouched, while a non-contiguous `buf` may have
+/// been advanced past the control bytes.
+///
+/// ```
+/// use bytes::Buf;
+/// let (len, bytes) = streamvb::encode(&[1, 300, 70000]);
+/// let (head, tail) = bytes.split_at(2);
+/// let mut buf = head.chain(tail).chain(&b"rest"[..]);
+/// assert_eq!(streamvb::buf::decode_from_buf(len, &mut buf).unwrap(), vec![1, 300, 70000]);
+/// assert_eq!(buf.chunk(), b"rest");
+/// ```
+pub fn decode_from_buf<B: Buf>(len: usize, buf: &mut B) -> Result<Vec<u32>, StreamVbyteError> {
+    let mut output = Vec::new();
+    if len == 0 {
+        return Ok(output);
+    }
+    let num_controls = control_bytes_len(len);
+    if buf.remaining() < num_controls {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+
+    let chunk = buf.chunk();
+    if chunk.len() >= num_controls {
+        let (control, data) = chunk.split_at(num_controls);
+        let data_len = data_bytes_len(control, len);
+        if data.len() >= data_len {
+            crate::decode_parts_into(len, control, &data[..data_len], &mut output)?;
+            buf.advance(num_controls + data_len);
+            return Ok(output);
+        }
+        // The data continues in the next chunk.
+        if buf.remaining() < num_controls + data_len {
+            return Err(StreamVbyteError::DecodeOutOfBounds);
+        }
+    }
+
+    let mut control = vec![0u8; num_controls];
+    buf.copy_to_slice(&mut control);
+    let data_len = data_bytes_len(&control, len);
+    if buf.remaining() < data_len {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    let mut data = vec![0u8; data_len];
+    buf.copy_to_slice(&mut data);
+    crate::decode_parts_into(len, &control, &data, &mut output)?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::{Buf, BufMut, BytesMut};
+
+    use super::{decode_from_buf, encode_into_buf};
+
+    fn sample_inputs() -> Vec<Vec<u32>> {
+        vec![
+            vec![],
+            vec![7],
+            vec![1, 288, 3, 123123, 83291],
+            (0..5000).map(|x| x * x * 13).collect(),
+        ]
+    }
+
+    #[test]
+    fn encode_matches() {
+        for input in sample_inputs() {
+            let (_, want) = crate::encode(&input);
+
+            let mut contiguous = BytesMut::with_capacity(crate::max_compressed_len(input.len()));
+            assert_eq!(encode_into_buf(&input, &mut contiguous), want.len());
+            assert_eq!(&contiguous[..], &want[..]);
+
+            // Too small for the fast path, so this takes the staging route.
+            let mut limited = Vec::new().limit(want.len());
+            assert_eq!(encode_into_buf(&input, &mut limited), want.len());
+            assert_eq!(limited.into_inner(), want);
+        }
+    }
+
+    #[test]
+    fn decode_chunked() {
+        for input in sample_inputs() {
+            let (len, mut bytes) = crate::encode(&input);
+            bytes.extend_from_slice(b"tail");
+            let total = bytes.len();
+            for split in [0, 1, len / 4, len / 4 + 1, total / 2, total - 4] {
+                let (a, b) = bytes.split_at(split.min(total));
+                let mut buf = a.chain(b);
+                assert_eq!(decode_from_buf(len, &mut buf).unwrap(), input);
+                assert_eq!(buf.remaining(), 4);
+            }
+        }
+    }
+
+    #[test]
+    fn truncated() {
+        let (len, bytes) = crate::encode(&[1, 288, 3, 123123, 83291]);
+        let mut short = &bytes[..bytes.len() - 1];
+        assert!(decode_from_buf(len, &mut short).is_err());
+        assert_eq!(short.len(), bytes.len() - 1);
+        let (a, b) = bytes[..bytes.len() - 1].split_at(3);
+        assert!(decode_from_buf(len, &mut a.chain(b)).is_err());
+    }
+}
@@ -1,35 +1,346 @@
 use std::mem;
 
+use crate::tables::len::LENGTH_TABLE;
+
 /// Maximum length of the compressed output vector where control bytes and
 /// data bytes are combined into one vector.
+///
+/// Saturates at `usize::MAX` for lengths too large to ever be encoded; use
+/// [checked_max_compressed_len] to detect that case.
 pub fn max_compressed_len(input_len: usize) -> usize {
-    let max_data_bytes = input_len * mem::size_of::<u32>();
-    control_bytes_len(input_len) + max_data_bytes
+    checked_max_compressed_len(input_len).unwrap_or(usize::MAX)
+}
+
+/// Like [max_compressed_len], but returns `None` on overflow.
+pub fn checked_max_compressed_len(input_len: usize) -> Option<usize> {
+    input_len
+        .checked_mul(mem::size_of::<u32>())?
+        .checked_add(control_bytes_len(input_len))
 }
 
 /// Exact number of control bytes in the compressed output vector.
 pub fn control_bytes_len(input_len: usize) -> usize {
     // 4 numbers per control byte (2 bits per input), round up to next byte
-    (input_len + 3) / 4
+    input_len.div_ceil(4)
 }
 
 /// Compute the exact compressed data length in bytes. `O(n)` because it needs
-/// to read the full input.
+/// to read the full input. Uses SIMD if available.
+#[allow(clippy::needless_return)]
 pub fn exact_compressed_len(input: &[u32]) -> usize {
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    ))]
+    {
+        use crate::x86_64::{encode::NoEncode, len::data_len_simd};
+        let (n, len) = data_len_simd::<NoEncode>(input);
+        return len + scalar_data_len(input[n..].iter().copied());
+    }
+    #[cfg(not(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    )))]
+    {
+        scalar_data_len(input.iter().copied())
+    }
+}
+
+/// Like [exact_compressed_len], but computes the length the data would have
+/// after zigzag encoding, as done by the `zigzag_*` functions.
+#[allow(clippy::needless_return)]
+pub fn exact_compressed_len_zigzag(input: &[u32]) -> usize {
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    ))]
+    {
+        use crate::x86_64::{encode::ZigZagEncode, len::data_len_simd};
+        let (n, len) = data_len_simd::<ZigZagEncode>(input);
+        return len + scalar_data_len(input[n..].iter().map(|&x| zigzag_encode_1(x)));
+    }
+    #[cfg(not(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    )))]
+    {
+        scalar_data_len(input.iter().map(|&x| zigzag_encode_1(x)))
+    }
+}
+
+/// Like [exact_compressed_len], but computes the length the data would have
+/// after delta coding, as done by [crate::delta::encode].
+pub fn exact_compressed_len_delta(input: &[u32]) -> usize {
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    ))]
+    let (n, len) = crate::x86_64::len::delta_data_len_simd(input);
+    #[cfg(not(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    )))]
+    let (n, len) = (0, 0);
+
+    let mut prev = if n > 0 { input[n - 1] } else { 0 };
+    len + scalar_data_len(input[n..].iter().map(|&x| {
+        let delta = x.wrapping_sub(prev);
+        prev = x;
+        delta
+    }))
+}
+
+/// Like [exact_compressed_len], but computes the length the data would have
+/// after frame-of-reference coding, i.e., after subtracting `reference` from
+/// each value. The reference is usually the minimum of the input.
+pub fn exact_compressed_len_for(input: &[u32], reference: u32) -> usize {
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    ))]
+    let (n, len) = crate::x86_64::len::reference_data_len_simd(input, reference);
+    #[cfg(not(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    )))]
+    let (n, len) = (0, 0);
+
+    len + scalar_data_len(input[n..].iter().map(|&x| x.wrapping_sub(reference)))
+}
+
+fn scalar_data_len<I: Iterator<Item = u32>>(input: I) -> usize {
     let mut len = 0;
     for value in input {
-        let t1 = (*value > 0x000000ff) as u32;
-        let t2 = (*value > 0x0000ffff) as u32;
-        let t3 = (*value > 0x00ffffff) as u32;
+        let t1 = (value > 0x000000ff) as u32;
+        let t2 = (value > 0x0000ffff) as u32;
+        let t3 = (value > 0x00ffffff) as u32;
         len += (t1 + t2 + t3 + 1) as usize;
     }
     len
 }
 
+/// The 2-bit code of `word`, i.e., its width in bytes minus one.
+#[inline]
+pub(crate) fn encode_one(word: u32) -> u32 {
+    let t1 = (word > 0x000000ff) as u32;
+    let t2 = (word > 0x0000ffff) as u32;
+    let t3 = (word > 0x00ffffff) as u32;
+    t1 + t2 + t3
+}
+
+#[inline]
+pub(crate) fn zigzag_encode_1(x: u32) -> u32 {
+    let x: i32 = x as i32;
+    (x as u32).wrapping_add(x as u32) ^ ((x >> 31) as u32)
+}
+
+#[inline]
+pub(crate) fn zigzag_decode_1(x: u32) -> u32 {
+    (x >> 1) ^ (0u32.wrapping_sub(x & 1))
+}
+
+// Transforms decoded values, one at a time and in order, so a decoder may
+// carry state from one value to the next. The SIMD decoders add a vectorised
+// version of the same transform on top.
+pub(crate) trait ScalarDecoder {
+    fn decode_1(&mut self, x: u32) -> u32;
+}
+
+#[derive(Default)]
+pub(crate) struct NoDecode;
+impl ScalarDecoder for NoDecode {
+    #[inline]
+    fn decode_1(&mut self, x: u32) -> u32 {
+        x
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ZigZagDecode;
+impl ScalarDecoder for ZigZagDecode {
+    #[inline]
+    fn decode_1(&mut self, x: u32) -> u32 {
+        zigzag_decode_1(x)
+    }
+}
+
+/// Applies `inner`, then adds up the values, starting from `prev`.
+pub(crate) struct DeltaDecode<D> {
+    pub(crate) inner: D,
+    pub(crate) prev: u32,
+}
+
+impl<D> DeltaDecode<D> {
+    pub(crate) fn new(inner: D, prev: u32) -> Self {
+        DeltaDecode { inner, prev }
+    }
+}
+
+impl<D: ScalarDecoder> ScalarDecoder for DeltaDecode<D> {
+    #[inline]
+    fn decode_1(&mut self, x: u32) -> u32 {
+        self.prev = self.prev.wrapping_add(self.inner.decode_1(x));
+        self.prev
+    }
+}
+
+/// Adds `reference` to every value.
+pub(crate) struct FrameOfReferenceDecode {
+    pub(crate) reference: u32,
+}
+
+impl FrameOfReferenceDecode {
+    pub(crate) fn new(reference: u32) -> Self {
+        FrameOfReferenceDecode { reference }
+    }
+}
+
+impl ScalarDecoder for FrameOfReferenceDecode {
+    #[inline]
+    fn decode_1(&mut self, x: u32) -> u32 {
+        x.wrapping_add(self.reference)
+    }
+}
+
+/// Number of data bytes used by the first `len` values, computed from the
+/// control bytes only. `control` must hold at least `control_bytes_len(len)`
+/// bytes.
+pub(crate) fn data_bytes_len(control: &[u8], len: usize) -> usize {
+    let full_controls = len / 4;
+    let mut total: usize = control[..full_controls]
+        .iter()
+        .map(|&key| LENGTH_TABLE[key as usize] as usize)
+        .sum();
+    for i in 0..len % 4 {
+        total += ((control[full_controls] >> (2 * i)) & 0x3) as usize + 1;
+    }
+    total
+}
+
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum StreamVbyteError {
     DecodeOutOfBounds,
+    /// A size computation overflowed or memory could not be allocated.
+    AllocationFailed,
+    /// The input exceeds a limit set in [crate::DecodeOptions].
+    LimitExceeded,
+    /// The input continues after the encoded values and
+    /// [crate::DecodeOptions::reject_trailing_bytes] is set.
+    TrailingBytes,
+    /// The input is not what [crate::encode] would produce and
+    /// [crate::DecodeOptions::require_canonical] is set.
+    NonCanonical,
+    /// The requested [crate::Backend] is not available on this host.
+    UnsupportedBackend,
+    /// A [crate::varint] value does not fit into the requested integer type.
+    InvalidVarint,
+    /// A block header of a block-based layout such as [crate::interleaved] or
+    /// [crate::adaptive] is invalid.
+    InvalidBlockHeader,
+}
+
+impl std::fmt::Display for StreamVbyteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamVbyteError::DecodeOutOfBounds => {
+                f.write_str("decoding tried to read past the end of the input")
+            }
+            StreamVbyteError::AllocationFailed => f.write_str("failed to allocate output buffer"),
+            StreamVbyteError::LimitExceeded => f.write_str("input exceeds the configured limits"),
+            StreamVbyteError::TrailingBytes => {
+                f.write_str("input has bytes after the encoded values")
+            }
+            StreamVbyteError::NonCanonical => f.write_str("input is not canonically encoded"),
+            StreamVbyteError::UnsupportedBackend => {
+                f.write_str("backend is not available on this host")
+            }
+            StreamVbyteError::InvalidVarint => f.write_str("varint is too long for its type"),
+            StreamVbyteError::InvalidBlockHeader => f.write_str("invalid block header"),
+        }
+    }
 }
 
+impl std::error::Error for StreamVbyteError {}
+
+/// `n` pseudo-random test values, with every byte width represented.
 #[cfg(test)]
-mod tests {}
+pub(crate) fn sample_values(n: usize) -> Vec<u32> {
+    (0..n as u32)
+        .map(|x| x.wrapping_mul(2654435761) >> (x % 32))
+        .collect()
+}
+
+/// Test inputs: the empty input, `edges`, and [sample_values] of a few
+/// lengths around the SIMD block sizes.
+#[cfg(test)]
+pub(crate) fn sample_inputs(edges: &[u32]) -> Vec<Vec<u32>> {
+    let mut inputs = vec![vec![], vec![0], edges.to_vec()];
+    for n in [3, 16, 17, 100, 1003] {
+        inputs.push(sample_values(n));
+    }
+    inputs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_inputs() -> Vec<Vec<u32>> {
+        let mut inputs = vec![
+            vec![],
+            vec![0],
+            vec![255, 256, 65535, 65536, 0xffffff, 0x1000000, u32::MAX],
+            (0..1000).map(|x| x * x * 31).collect(),
+            (0..1003)
+                .map(|x| (x as u32).wrapping_mul(0x9e3779b9))
+                .collect(),
+        ];
+        inputs.push(inputs[2].iter().rev().copied().collect());
+        inputs
+    }
+
+    #[test]
+    fn exact_len_matches_encoding() {
+        for input in sample_inputs() {
+            let (len, bytes) = crate::scalar::encode(&input);
+            let control = control_bytes_len(len);
+            assert_eq!(exact_compressed_len(&input), bytes.len() - control);
+            assert!(bytes.len() <= max_compressed_len(len));
+
+            let zigzag: Vec<u32> = input.iter().map(|&x| zigzag_encode_1(x)).collect();
+            assert_eq!(
+                exact_compressed_len_zigzag(&input),
+                crate::scalar::encode(&zigzag).1.len() - control
+            );
+            assert_eq!(
+                exact_compressed_len_delta(&input),
+                crate::delta::encode(&input).1.len() - control
+            );
+
+            let min = input.iter().copied().min().unwrap_or(0);
+            let shifted: Vec<u32> = input.iter().map(|&x| x - min).collect();
+            assert_eq!(
+                exact_compressed_len_for(&input, min),
+                crate::scalar::encode(&shifted).1.len() - control
+            );
+        }
+    }
+
+    #[test]
+    fn zigzag() {
+        assert_eq!(zigzag_encode_1(0), 0);
+        assert_eq!(zigzag_encode_1(-1i32 as u32), 1);
+        assert_eq!(zigzag_encode_1(1), 2);
+        assert_eq!(zigzag_encode_1(i32::MAX as u32), u32::MAX - 1);
+        assert_eq!(zigzag_encode_1(i32::MIN as u32), u32::MAX);
+    }
+}
@@ -0,0 +1,375 @@
+//! Containers that keep the length and the encoded bytes together.
+//!
+//! [CompressedVec] owns its bytes and can grow, [CompressedSlice] borrows
+//! them, e.g. from a memory-mapped file. Both store the control and data
+//! streams separately, so appending never has to move the data bytes.
+//!
+//! Equality and hashing compare the encoded bytes. For encodings produced by
+//! this crate that is the same as comparing the values.
+use std::iter::FusedIterator;
+
+use crate::{
+    common::{control_bytes_len, data_bytes_len, StreamVbyteError},
+    tables::len::LENGTH_TABLE,
+};
+
+/// An owned, growable, encoded sequence of `u32` values.
+///
+/// ```
+/// use streamvb::CompressedVec;
+/// let mut values = CompressedVec::from_slice(&[1, 300, 70000]);
+/// values.extend_from_slice(&[5, 0xdeadbeef]);
+/// assert_eq!(values.len(), 5);
+/// assert_eq!(values.get(4), Some(0xdeadbeef));
+/// assert_eq!(values.iter().collect::<Vec<_>>(), vec![1, 300, 70000, 5, 0xdeadbeef]);
+/// assert_eq!(values.to_bytes(), streamvb::encode(&values.to_vec()).1);
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Hash, Debug)]
+pub struct CompressedVec {
+    len: usize,
+    control: Vec<u8>,
+    data: Vec<u8>,
+}
+
+impl CompressedVec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encode `values`.
+    pub fn from_slice(values: &[u32]) -> Self {
+        let mut result = Self::new();
+        result.extend_from_slice(values);
+        result
+    }
+
+    /// Take ownership of `(len, bytes)` as returned by [crate::encode].
+    ///
+    /// Returns an error if `bytes` is too short for `len` values.
+    pub fn from_encoded(len: usize, mut bytes: Vec<u8>) -> Result<Self, StreamVbyteError> {
+        let slice = CompressedSlice::new(len, &bytes)?;
+        let num_controls = slice.control.len();
+        let data_len = slice.data.len();
+        let control = bytes[..num_controls].to_vec();
+        bytes.drain(..num_controls);
+        bytes.truncate(data_len);
+        Ok(CompressedVec {
+            len,
+            control,
+            data: bytes,
+        })
+    }
+
+    /// Borrow the contents as a [CompressedSlice].
+    pub fn as_slice(&self) -> CompressedSlice<'_> {
+        CompressedSlice {
+            len: self.len,
+            control: &self.control,
+            data: &self.data,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Decode the value at `index`. Takes time linear in `index / 4`.
+    pub fn get(&self, index: usize) -> Option<u32> {
+        self.as_slice().get(index)
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        self.as_slice().iter()
+    }
+
+    /// Decode all values.
+    pub fn to_vec(&self) -> Vec<u32> {
+        self.as_slice().to_vec()
+    }
+
+    /// Decode all values and append them to `output`.
+    pub fn decode_into(&self, output: &mut Vec<u32>) {
+        self.as_slice().decode_into(output)
+    }
+
+    /// The encoded bytes in the layout produced by [crate::encode].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.as_slice().to_bytes()
+    }
+
+    /// Append `values`. If the last control byte is only partially used, the
+    /// values belonging to it are re-encoded together with the new ones.
+    pub fn extend_from_slice(&mut self, values: &[u32]) {
+        if values.is_empty() {
+            return;
+        }
+        let partial = self.len % 4;
+        let (_, bytes) = if partial == 0 {
+            crate::encode(values)
+        } else {
+            let start = self.len - partial;
+            let key = self.control.pop().unwrap();
+            let tail_data_len = (0..partial)
+                .map(|i| ((key >> (2 * i)) & 0x3) as usize + 1)
+                .sum::<usize>();
+            let tail_start = self.data.len() - tail_data_len;
+            let mut tail = Vec::with_capacity(partial + values.len());
+            let mut offset = tail_start;
+            for i in 0..partial {
+                let width = ((key >> (2 * i)) & 0x3) as usize + 1;
+                tail.push(read_value(&self.data[offset..offset + width]));
+                offset += width;
+            }
+            tail.extend_from_slice(values);
+            self.data.truncate(tail_start);
+            self.len = start;
+            crate::encode(&tail)
+        };
+        let added = partial + values.len();
+        let (control, data) = bytes.split_at(control_bytes_len(added));
+        self.control.extend_from_slice(control);
+        self.data.extend_from_slice(data);
+        self.len += added;
+    }
+}
+
+impl From<&[u32]> for CompressedVec {
+    fn from(values: &[u32]) -> Self {
+        Self::from_slice(values)
+    }
+}
+
+impl<'a> IntoIterator for &'a CompressedVec {
+    type Item = u32;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+/// A borrowed, encoded sequence of `u32` values.
+///
+/// ```
+/// let (len, bytes) = streamvb::encode(&[1, 300, 70000]);
+/// let values = streamvb::CompressedSlice::new(len, &bytes).unwrap();
+/// assert_eq!(values.get(1), Some(300));
+/// assert_eq!(values.to_vec(), vec![1, 300, 70000]);
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Debug)]
+pub struct CompressedSlice<'a> {
+    len: usize,
+    control: &'a [u8],
+    data: &'a [u8],
+}
+
+impl<'a> CompressedSlice<'a> {
+    /// View `(len, bytes)` as returned by [crate::encode]. Bytes after the end
+    /// of the encoded values are ignored.
+    ///
+    /// Returns an error if `bytes` is too short for `len` values.
+    pub fn new(len: usize, bytes: &'a [u8]) -> Result<Self, StreamVbyteError> {
+        let num_controls = control_bytes_len(len);
+        if num_controls > bytes.len() {
+            return Err(StreamVbyteError::DecodeOutOfBounds);
+        }
+        let (control, data) = bytes.split_at(num_controls);
+        let data_len = data_bytes_len(control, len);
+        if data_len > data.len() {
+            return Err(StreamVbyteError::DecodeOutOfBounds);
+        }
+        Ok(CompressedSlice {
+            len,
+            control,
+            data: &data[..data_len],
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Decode the value at `index`. Takes time linear in `index / 4`.
+    pub fn get(&self, index: usize) -> Option<u32> {
+        if index >= self.len {
+            return None;
+        }
+        let key = self.control[index / 4];
+        let mut offset: usize = self.control[..index / 4]
+            .iter()
+            .map(|&key| LENGTH_TABLE[key as usize] as usize)
+            .sum();
+        for i in 0..index % 4 {
+            offset += ((key >> (2 * i)) & 0x3) as usize + 1;
+        }
+        let width = ((key >> (2 * (index % 4))) & 0x3) as usize + 1;
+        Some(read_value(&self.data[offset..offset + width]))
+    }
+
+    pub fn iter(&self) -> Iter<'a> {
+        Iter {
+            slice: *self,
+            index: 0,
+            offset: 0,
+        }
+    }
+
+    /// Decode all values.
+    pub fn to_vec(&self) -> Vec<u32> {
+        let mut output = Vec::with_capacity(self.len);
+        self.decode_into(&mut output);
+        output
+    }
+
+    /// Decode all values and append them to `output`.
+    pub fn decode_into(&self, output: &mut Vec<u32>) {
+        crate::decode_parts_into(self.len, self.control, self.data, output)
+            .expect("sizes are checked on construction");
+    }
+
+    /// The encoded bytes in the layout produced by [crate::encode].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.control.len() + self.data.len());
+        bytes.extend_from_slice(self.control);
+        bytes.extend_from_slice(self.data);
+        bytes
+    }
+}
+
+impl<'a> IntoIterator for CompressedSlice<'a> {
+    type Item = u32;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+/// Iterator over the values of a [CompressedVec] or [CompressedSlice].
+#[derive(Clone, Debug)]
+pub struct Iter<'a> {
+    slice: CompressedSlice<'a>,
+    index: usize,
+    offset: usize,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.index >= self.slice.len {
+            return None;
+        }
+        let key = self.slice.control[self.index / 4];
+        let width = ((key >> (2 * (self.index % 4))) & 0x3) as usize + 1;
+        let value = read_value(&self.slice.data[self.offset..self.offset + width]);
+        self.index += 1;
+        self.offset += width;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.slice.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
+
+fn read_value(bytes: &[u8]) -> u32 {
+    let mut buf = [0u8; 4];
+    buf[..bytes.len()].copy_from_slice(bytes);
+    u32::from_le_bytes(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    use super::{CompressedSlice, CompressedVec};
+
+    fn sample() -> Vec<u32> {
+        (0..1003).map(|x| x * x * x).collect()
+    }
+
+    #[test]
+    fn access() {
+        let values = sample();
+        let compressed = CompressedVec::from(&values[..]);
+        assert_eq!(compressed.len(), values.len());
+        assert_eq!(compressed.to_vec(), values);
+        assert_eq!(compressed.iter().collect::<Vec<_>>(), values);
+        assert_eq!(compressed.iter().len(), values.len());
+        for (i, &value) in values.iter().enumerate() {
+            assert_eq!(compressed.get(i), Some(value));
+        }
+        assert_eq!(compressed.get(values.len()), None);
+
+        let mut output = vec![42];
+        compressed.decode_into(&mut output);
+        assert_eq!(output[1..], values[..]);
+    }
+
+    #[test]
+    fn extend() {
+        let values = sample();
+        for split in [0, 1, 2, 3, 4, 5, 7, 500, 1003] {
+            let mut compressed = CompressedVec::from_slice(&values[..split]);
+            let mid = split.max(501);
+            compressed.extend_from_slice(&values[split..mid]);
+            compressed.extend_from_slice(&values[mid..]);
+            assert_eq!(compressed, CompressedVec::from_slice(&values));
+            assert_eq!(compressed.to_bytes(), crate::encode(&values).1);
+        }
+        let mut one_by_one = CompressedVec::new();
+        for &value in &values {
+            one_by_one.extend_from_slice(&[value]);
+        }
+        assert_eq!(one_by_one.to_vec(), values);
+    }
+
+    #[test]
+    fn push_across_groups() {
+        let values: Vec<u32> = (0..13).map(|x| 1 << (x * 5 % 32)).collect();
+        let mut compressed = CompressedVec::new();
+        for (i, &value) in values.iter().enumerate() {
+            compressed.extend_from_slice(&[value]);
+            assert_eq!(compressed.len(), i + 1);
+            assert_eq!(compressed.to_bytes(), crate::encode(&values[..=i]).1);
+        }
+    }
+
+    #[test]
+    fn borrowed() {
+        let values = sample();
+        let (len, mut bytes) = crate::encode(&values);
+        let owned = CompressedVec::from_encoded(len, bytes.clone()).unwrap();
+        bytes.extend_from_slice(b"trailing");
+        let slice = CompressedSlice::new(len, &bytes).unwrap();
+        assert_eq!(slice, owned.as_slice());
+        assert_eq!(slice.to_vec(), values);
+        assert_eq!(slice.get(1002), values.last().copied());
+
+        let hash = |x: &dyn Fn(&mut DefaultHasher)| {
+            let mut hasher = DefaultHasher::new();
+            x(&mut hasher);
+            hasher.finish()
+        };
+        let clone = owned.clone();
+        assert_eq!(hash(&|h| owned.hash(h)), hash(&|h| clone.hash(h)));
+
+        assert!(CompressedSlice::new(len, &bytes[..100]).is_err());
+        assert!(CompressedVec::from_encoded(len + 1, crate::encode(&values).1).is_err());
+    }
+}
@@ -0,0 +1,296 @@
+//! Byte-exact conformance against the reference C implementation.
+//!
+//! The golden vectors in `testdata/golden` were produced by
+//! `testdata/golden/gen_golden.c`, with one record for every length from 0
+//! to 1000. Every backend must produce exactly those bytes and decode them
+//! back to the original values.
+use crate::common::{zigzag_decode_1, zigzag_encode_1};
+use crate::delta::{differences, prefix_sum};
+
+type EncodeFn = fn(&[u32]) -> (usize, Vec<u8>);
+type DecodeFn = fn(usize, &[u8]) -> Vec<u32>;
+
+static PLAIN: &[u8] = include_bytes!("../testdata/golden/plain.bin");
+static ZIGZAG: &[u8] = include_bytes!("../testdata/golden/zigzag.bin");
+static DELTA: &[u8] = include_bytes!("../testdata/golden/delta.bin");
+static ZERO124: &[u8] = include_bytes!("../testdata/golden/0124.bin");
+
+const EDGES: [u32; 12] = [
+    0, 1, 255, 256, 65535, 65536, 16777215, 16777216, 2147483647, 2147483648, 4294967294,
+    4294967295,
+];
+
+// Mirrors `generate` in `gen_golden.c`.
+fn golden_input(count: u32) -> Vec<u32> {
+    let mut state: u32 = 0x9e3779b9 ^ count;
+    (0..count)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            let x = state;
+            match x & 7 {
+                0 => EDGES[((x >> 3) % EDGES.len() as u32) as usize],
+                1 => x >> 24,
+                2 => x >> 16,
+                3 => x >> 8,
+                4 => x,
+                5 => 0,
+                6 => x >> 28,
+                _ => x >> 12,
+            }
+        })
+        .collect()
+}
+
+// Returns `(values, encoded bytes)` for every record in a golden file.
+fn records(mut file: &[u8]) -> Vec<(Vec<u32>, &[u8])> {
+    fn read_u32(file: &mut &[u8]) -> u32 {
+        let (head, tail) = file.split_at(4);
+        *file = tail;
+        u32::from_le_bytes([head[0], head[1], head[2], head[3]])
+    }
+    let mut result = Vec::new();
+    while !file.is_empty() {
+        let count = read_u32(&mut file);
+        let nbytes = read_u32(&mut file) as usize;
+        let (bytes, tail) = file.split_at(nbytes);
+        file = tail;
+        result.push((golden_input(count), bytes));
+    }
+    assert_eq!(result.len(), 1001, "golden file is incomplete");
+    result
+}
+
+fn check(file: &[u8], encoders: &[(&str, EncodeFn)], decoders: &[(&str, DecodeFn)]) {
+    // Every length is too slow under Miri. The short ones and a sample of the
+    // rest still cover every tail length and the main loops.
+    let sampled = |&(i, _): &(usize, _)| !cfg!(miri) || i <= 40 || i % 97 == 0;
+    for (_, (values, golden)) in records(file).into_iter().enumerate().filter(sampled) {
+        for (name, encode) in encoders {
+            let (len, bytes) = encode(&values);
+            assert_eq!(len, values.len(), "{}: wrong length", name);
+            assert_eq!(bytes, golden, "{}: encoding of {} values", name, len);
+        }
+        for (name, decode) in decoders {
+            let decoded = decode(values.len(), golden);
+            assert_eq!(
+                decoded,
+                values,
+                "{}: decoding of {} values",
+                name,
+                values.len()
+            );
+        }
+    }
+}
+
+fn zigzag(values: &[u32]) -> Vec<u32> {
+    values.iter().map(|&x| zigzag_encode_1(x)).collect()
+}
+
+fn unzigzag(mut values: Vec<u32>) -> Vec<u32> {
+    for value in values.iter_mut() {
+        *value = zigzag_decode_1(*value);
+    }
+    values
+}
+
+fn undelta(mut values: Vec<u32>) -> Vec<u32> {
+    prefix_sum(&mut values, 0);
+    values
+}
+
+#[test]
+fn plain() {
+    #[allow(unused_mut)]
+    let mut encoders: Vec<(&str, EncodeFn)> = vec![
+        ("scalar", crate::scalar::encode),
+        ("safe", crate::safe::encode),
+        ("default", crate::encode),
+    ];
+    #[allow(unused_mut)]
+    let mut decoders: Vec<(&str, DecodeFn)> = vec![
+        ("scalar", |len, input| {
+            crate::scalar::decode(len, input).unwrap()
+        }),
+        ("safe", |len, input| {
+            crate::safe::decode(len, input).unwrap()
+        }),
+        ("default", |len, input| crate::decode(len, input).unwrap()),
+    ];
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    ))]
+    {
+        use crate::x86_64::{decode::*, encode::*};
+        encoders.push(("ssse3", encode_simd::<NoEncode>));
+        decoders.push(("ssse3", |len, input| {
+            decode_simd::<NoDecode>(len, input).unwrap()
+        }));
+    }
+    #[cfg(all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ))]
+    {
+        use crate::aarch64::{decode::*, encode::*};
+        encoders.push(("neon", encode_simd::<NoEncode>));
+        decoders.push(("neon", |len, input| {
+            decode_simd::<NoDecode>(len, input).unwrap()
+        }));
+    }
+    check(PLAIN, &encoders, &decoders);
+}
+
+#[test]
+fn zigzag_plain() {
+    #[allow(unused_mut)]
+    let mut encoders: Vec<(&str, EncodeFn)> = vec![
+        ("scalar", |values| crate::scalar::encode(&zigzag(values))),
+        ("safe", |values| crate::safe::encode(&zigzag(values))),
+        ("context", |values| {
+            let mut encoder = crate::Encoder::with_transform(crate::Transform::ZigZag);
+            (values.len(), encoder.encode(values).to_vec())
+        }),
+    ];
+    #[allow(unused_mut)]
+    let mut decoders: Vec<(&str, DecodeFn)> = vec![
+        ("scalar", |len, input| {
+            unzigzag(crate::scalar::decode(len, input).unwrap())
+        }),
+        ("safe", |len, input| {
+            unzigzag(crate::safe::decode(len, input).unwrap())
+        }),
+        ("context", |len, input| {
+            let mut decoder = crate::Decoder::with_transform(crate::Transform::ZigZag);
+            decoder.decode(len, input).unwrap().to_vec()
+        }),
+    ];
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    ))]
+    {
+        use crate::x86_64::{decode::*, encode::*};
+        encoders.push(("ssse3", encode_simd::<ZigZagEncode>));
+        decoders.push(("ssse3", |len, input| {
+            decode_simd::<ZigZagDecode>(len, input).unwrap()
+        }));
+    }
+    #[cfg(all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ))]
+    {
+        use crate::aarch64::{decode::*, encode::*};
+        encoders.push(("neon", encode_simd::<ZigZagEncode>));
+        decoders.push(("neon", |len, input| {
+            decode_simd::<ZigZagDecode>(len, input).unwrap()
+        }));
+    }
+    #[cfg(any(
+        all(
+            any(target_arch = "x86", target_arch = "x86_64"),
+            target_feature = "ssse3",
+            not(feature = "safe-only")
+        ),
+        all(
+            target_arch = "aarch64",
+            feature = "aarch64-simd",
+            not(feature = "safe-only")
+        )
+    ))]
+    {
+        encoders.push(("simd", |values| {
+            let mut output = Vec::new();
+            crate::simd::zigzag_encode_into(values, &mut output);
+            (values.len(), output)
+        }));
+        decoders.push(("simd", |len, input| {
+            let mut output = Vec::new();
+            crate::simd::zigzag_decode_into(len, input, &mut output).unwrap();
+            output
+        }));
+    }
+    check(ZIGZAG, &encoders, &decoders);
+}
+
+#[test]
+fn delta() {
+    #[allow(unused_mut)]
+    let mut encoders: Vec<(&str, EncodeFn)> = vec![
+        ("scalar", |values| {
+            crate::scalar::encode(&differences(values))
+        }),
+        ("safe", |values| crate::safe::encode(&differences(values))),
+        ("default", crate::delta::encode),
+        ("context", |values| {
+            let mut encoder = crate::Encoder::with_transform(crate::Transform::Delta);
+            (values.len(), encoder.encode(values).to_vec())
+        }),
+    ];
+    #[allow(unused_mut)]
+    let mut decoders: Vec<(&str, DecodeFn)> = vec![
+        ("scalar", |len, input| {
+            undelta(crate::scalar::decode(len, input).unwrap())
+        }),
+        ("safe", |len, input| {
+            undelta(crate::safe::decode(len, input).unwrap())
+        }),
+        ("default", |len, input| {
+            crate::delta::decode(len, input).unwrap()
+        }),
+        ("context", |len, input| {
+            let mut decoder = crate::Decoder::with_transform(crate::Transform::Delta);
+            decoder.decode(len, input).unwrap().to_vec()
+        }),
+    ];
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    ))]
+    {
+        use crate::x86_64::{decode::*, encode::*};
+        encoders.push(("ssse3", |values| {
+            encode_simd::<NoEncode>(&differences(values))
+        }));
+        decoders.push(("ssse3", |len, input| {
+            undelta(decode_simd::<NoDecode>(len, input).unwrap())
+        }));
+    }
+    #[cfg(all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ))]
+    {
+        use crate::aarch64::{decode::*, encode::*};
+        encoders.push(("neon", |values| {
+            encode_simd::<NoEncode>(&differences(values))
+        }));
+        decoders.push(("neon", |len, input| {
+            undelta(decode_simd::<NoDecode>(len, input).unwrap())
+        }));
+    }
+    check(DELTA, &encoders, &decoders);
+}
+
+// 0124 has a single implementation shared by all backends.
+
+#[test]
+fn zero124() {
+    check(
+        ZERO124,
+        &[("scalar", crate::zero124::encode)],
+        &[("scalar", |len, input| {
+            crate::zero124::decode(len, input).unwrap()
+        })],
+    );
+}
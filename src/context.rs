@@ -0,0 +1,238 @@
+//! Reusable encoding and decoding contexts.
+//!
+//! [Encoder] and [Decoder] keep their output buffers between calls and hand
+//! out borrowed slices, so code that encodes or decodes many small lists only
+//! pays for allocation once.
+//!
+//! The contexts always use the implementation that [crate::encode] and
+//! [crate::decode] pick at compile time. Choosing another one per context is
+//! not supported.
+use crate::common::{control_bytes_len, StreamVbyteError};
+use crate::delta::{differences_into, prefix_sum};
+
+/// Transformation applied to the values before encoding and undone after
+/// decoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Transform {
+    /// Encode the values as they are, like [crate::encode].
+    #[default]
+    None,
+    /// Zigzag encode values first, for signed values stored as `u32`.
+    ZigZag,
+    /// Encode differences to the previous value, like [crate::delta::encode].
+    Delta,
+}
+
+/// Encodes values into an internal, reused buffer.
+///
+/// ```
+/// let mut encoder = streamvb::Encoder::new();
+/// for list in [&[1, 2, 3][..], &[300, 70000]] {
+///     let bytes = encoder.encode(list);
+///     assert_eq!(bytes, &streamvb::encode(list).1[..]);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Encoder {
+    transform: Transform,
+    bytes: Vec<u8>,
+    scratch: Vec<u32>,
+}
+
+impl Encoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_transform(transform: Transform) -> Self {
+        Encoder {
+            transform,
+            ..Self::default()
+        }
+    }
+
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    /// Encode `values` and return the encoded bytes, which stay valid until
+    /// the next call. Decode them with `values.len()` as the length.
+    pub fn encode(&mut self, values: &[u32]) -> &[u8] {
+        self.bytes.clear();
+        match self.transform {
+            Transform::None => crate::encode_into(values, &mut self.bytes),
+            Transform::ZigZag => zigzag_encode_into(values, &mut self.bytes, &mut self.scratch),
+            Transform::Delta => {
+                self.scratch.clear();
+                differences_into(values, &mut self.scratch);
+                crate::encode_into(&self.scratch, &mut self.bytes);
+            }
+        }
+        &self.bytes
+    }
+}
+
+/// Decodes values into an internal, reused buffer.
+///
+/// ```
+/// let mut decoder = streamvb::Decoder::new();
+/// let (len, bytes) = streamvb::encode(&[1, 300, 70000]);
+/// assert_eq!(decoder.decode(len, &bytes).unwrap(), &[1, 300, 70000]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Decoder {
+    transform: Transform,
+    values: Vec<u32>,
+}
+
+impl Decoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_transform(transform: Transform) -> Self {
+        Decoder {
+            transform,
+            ..Self::default()
+        }
+    }
+
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    /// Decode `len` values and return them. They stay valid until the next
+    /// call.
+    ///
+    /// Returns an error under the same conditions as [crate::decode].
+    pub fn decode(&mut self, len: usize, input: &[u8]) -> Result<&[u32], StreamVbyteError> {
+        self.values.clear();
+        if len == 0 {
+            return Ok(&self.values);
+        }
+        let num_controls = control_bytes_len(len);
+        if num_controls >= input.len() {
+            return Err(StreamVbyteError::DecodeOutOfBounds);
+        }
+        let (control, data) = input.split_at(num_controls);
+        match self.transform {
+            Transform::None => crate::decode_parts_into(len, control, data, &mut self.values)?,
+            Transform::ZigZag => zigzag_decode_parts_into(len, control, data, &mut self.values)?,
+            Transform::Delta => {
+                crate::decode_parts_into(len, control, data, &mut self.values)?;
+                prefix_sum(&mut self.values, 0);
+            }
+        }
+        Ok(&self.values)
+    }
+}
+
+// Zigzag encodes `values` and appends the encoded bytes to `output`. The SIMD
+// kernels do this in one pass, the scalar code goes through `scratch`.
+#[allow(clippy::needless_return, unused_variables)]
+fn zigzag_encode_into(values: &[u32], output: &mut Vec<u8>, scratch: &mut Vec<u32>) {
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    ))]
+    {
+        crate::x86_64::encode::encode_into_simd::<crate::x86_64::encode::ZigZagEncode>(
+            values, output,
+        );
+        return;
+    }
+
+    #[cfg(all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ))]
+    {
+        crate::aarch64::encode::encode_into_simd::<crate::aarch64::encode::ZigZagEncode>(
+            values, output,
+        );
+        return;
+    }
+
+    #[allow(unreachable_code)]
+    {
+        scratch.clear();
+        scratch.extend(values.iter().map(|&x| crate::common::zigzag_encode_1(x)));
+        crate::encode_into(scratch, output);
+    }
+}
+
+// Like [crate::decode_parts_into], but also zigzag decodes the values.
+#[allow(clippy::needless_return)]
+fn zigzag_decode_parts_into(
+    len: usize,
+    control: &[u8],
+    data: &[u8],
+    output: &mut Vec<u32>,
+) -> Result<(), StreamVbyteError> {
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    ))]
+    {
+        return crate::x86_64::decode::decode_parts_into_simd::<crate::x86_64::decode::ZigZagDecode>(
+            len, control, data, output,
+        );
+    }
+
+    #[cfg(all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ))]
+    {
+        return crate::aarch64::decode::decode_parts_into_simd::<
+            crate::aarch64::decode::ZigZagDecode,
+        >(len, control, data, output);
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let start = output.len();
+        crate::decode_parts_into(len, control, data, output)?;
+        for value in &mut output[start..] {
+            *value = crate::common::zigzag_decode_1(*value);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Decoder, Encoder, Transform};
+
+    #[test]
+    fn roundtrip() {
+        let lists: Vec<Vec<u32>> = (0..50)
+            .map(|n| (0..n * 7).map(|x| x * x * 3 + n).collect())
+            .collect();
+        for transform in [Transform::None, Transform::ZigZag, Transform::Delta] {
+            let mut encoder = Encoder::with_transform(transform);
+            let mut decoder = Decoder::with_transform(transform);
+            for list in &lists {
+                let bytes = encoder.encode(list);
+                assert_eq!(decoder.decode(list.len(), bytes).unwrap(), &list[..]);
+            }
+            assert!(decoder.decode(10, &[0, 0]).is_err());
+        }
+    }
+
+    #[test]
+    fn matches_free_functions() {
+        let values: Vec<u32> = (0..100).map(|x| x * 1000).collect();
+        let mut encoder = Encoder::new();
+        assert_eq!(encoder.encode(&values), &crate::encode(&values).1[..]);
+        let mut encoder = Encoder::with_transform(Transform::Delta);
+        assert_eq!(
+            encoder.encode(&values),
+            &crate::delta::encode(&values).1[..]
+        );
+    }
+}
@@ -0,0 +1,214 @@
+//! Differential (delta) coding for sorted sequences.
+//!
+//! Instead of the values themselves, the difference to the previous value is
+//! encoded (the first value is stored as the difference to 0). For sorted
+//! inputs such as posting lists the differences are usually much smaller than
+//! the values and therefore compress better.
+//!
+//! The encoded bytes are compatible with `streamvbyte_delta_encode` from the
+//! reference C implementation when called with `prev = 0`.
+//!
+//! Differences are computed using wrapping arithmetic, so unsorted inputs can
+//! still be round-tripped, but they will usually compress worse than with
+//! [crate::encode].
+mod search;
+mod setops;
+
+pub use search::{contains, lower_bound, SkipIndex};
+pub use setops::{intersect, intersect_many, union, union_many};
+
+use crate::common::{control_bytes_len, data_bytes_len, StreamVbyteError};
+use crate::options::DecodeOptions;
+
+/// Encode a slice of `u32` values as differences to the previous value.
+///
+/// ```
+/// let values = vec![10, 20, 300, 310, 70000];
+/// let (len, bytes) = streamvb::delta::encode(&values);
+/// assert_eq!(len, 5);
+/// assert_eq!(streamvb::delta::decode(len, &bytes).unwrap(), values);
+/// ```
+pub fn encode(values: &[u32]) -> (usize, Vec<u8>) {
+    crate::encode(&differences(values))
+}
+
+/// Like [encode], but also builds a [SkipIndex] with an entry every `interval`
+/// control bytes. This is cheaper than calling [SkipIndex::build] later.
+///
+/// Panics if `interval` is 0.
+pub fn encode_with_index(values: &[u32], interval: usize) -> (usize, Vec<u8>, SkipIndex) {
+    let (len, bytes) = encode(values);
+    let index = SkipIndex::from_encoded(values, &bytes, interval);
+    (len, bytes, index)
+}
+
+/// Decode bytes encoded using [encode] into the original `u32` values.
+///
+/// Returns an error if the decoding process tried to read bytes outside of the
+/// input slice.
+pub fn decode(len: usize, input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
+    let mut values = crate::decode(len, input)?;
+    prefix_sum(&mut values, 0);
+    Ok(values)
+}
+
+/// Like [decode], but applies the limits and checks in `options` to the
+/// encoded differences. Use this for untrusted input.
+pub fn decode_with_options(
+    len: usize,
+    input: &[u8],
+    options: &DecodeOptions,
+) -> Result<Vec<u32>, StreamVbyteError> {
+    let mut values = crate::decode_with_options(len, input, options)?;
+    prefix_sum(&mut values, 0);
+    Ok(values)
+}
+
+pub(crate) fn differences(values: &[u32]) -> Vec<u32> {
+    let mut output = Vec::with_capacity(values.len());
+    differences_into(values, &mut output);
+    output
+}
+
+/// Appends the differences of `values`, starting from 0, to `output`.
+pub(crate) fn differences_into(values: &[u32], output: &mut Vec<u32>) {
+    let mut prev = 0u32;
+    output.extend(values.iter().map(|&value| {
+        let delta = value.wrapping_sub(prev);
+        prev = value;
+        delta
+    }));
+}
+
+/// Turns differences back into values, starting from `prev`. Returns the last
+/// value.
+pub(crate) fn prefix_sum(values: &mut [u32], mut prev: u32) -> u32 {
+    for value in values.iter_mut() {
+        prev = prev.wrapping_add(*value);
+        *value = prev;
+    }
+    prev
+}
+
+/// Number of values decoded at once by [DeltaBlocks].
+pub(crate) const BLOCK_LEN: usize = 256;
+
+/// Decodes a delta-encoded stream [BLOCK_LEN] values at a time, so that
+/// consumers can stop early without decoding everything.
+pub(crate) struct DeltaBlocks<'a> {
+    control: &'a [u8],
+    data: &'a [u8],
+    remaining: usize,
+    prev: u32,
+}
+
+impl<'a> DeltaBlocks<'a> {
+    pub(crate) fn new(len: usize, input: &'a [u8]) -> Result<Self, StreamVbyteError> {
+        if len == 0 {
+            return Ok(Self::empty());
+        }
+        let num_control_bytes = control_bytes_len(len);
+        if num_control_bytes >= input.len() {
+            return Err(StreamVbyteError::DecodeOutOfBounds);
+        }
+        let (control, data) = input.split_at(num_control_bytes);
+        Ok(DeltaBlocks {
+            control,
+            data,
+            remaining: len,
+            prev: 0,
+        })
+    }
+
+    pub(crate) fn empty() -> Self {
+        DeltaBlocks {
+            control: &[],
+            data: &[],
+            remaining: 0,
+            prev: 0,
+        }
+    }
+
+    /// Replace the contents of `output` with the next block of values. Returns
+    /// `false` if there are no values left.
+    pub(crate) fn next_into(&mut self, output: &mut Vec<u32>) -> Result<bool, StreamVbyteError> {
+        output.clear();
+        if self.remaining == 0 {
+            return Ok(false);
+        }
+        let n = self.remaining.min(BLOCK_LEN);
+        crate::decode_parts_into(n, self.control, self.data, output)?;
+        // Decoding succeeded, so these are in bounds.
+        let used_data = data_bytes_len(self.control, n);
+        self.control = &self.control[control_bytes_len(n)..];
+        self.data = &self.data[used_data..];
+        self.remaining -= n;
+        self.prev = prefix_sum(output, self.prev);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode, DeltaBlocks, BLOCK_LEN};
+    use crate::{DecodeOptions, StreamVbyteError};
+
+    #[test]
+    fn encode_decode() {
+        let inputs: &[Vec<u32>] = &[
+            vec![],
+            vec![42],
+            vec![1, 2, 3],
+            vec![1, 288, 300, 94320],
+            vec![1000, 2000, 3000, 3001, 70000],
+            (0..1000).map(|x| x * 7).collect(),
+            // Unsorted input round-trips thanks to wrapping arithmetic.
+            vec![5, 3, u32::MAX, 0, 17],
+        ];
+        for input in inputs {
+            let (len, bytes) = encode(input);
+            assert_eq!(&decode(len, &bytes).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn decode_with_options() {
+        let values: Vec<u32> = (0..100).map(|x| x * 1000).collect();
+        let (len, mut bytes) = encode(&values);
+        let strict = DecodeOptions::new().reject_trailing_bytes(true);
+        assert_eq!(
+            super::decode_with_options(len, &bytes, &strict).unwrap(),
+            values
+        );
+        assert!(matches!(
+            super::decode_with_options(len, &bytes, &strict.max_len(99)),
+            Err(StreamVbyteError::LimitExceeded)
+        ));
+        bytes.push(0);
+        assert!(matches!(
+            super::decode_with_options(len, &bytes, &strict),
+            Err(StreamVbyteError::TrailingBytes)
+        ));
+    }
+
+    #[test]
+    fn deltas_are_encoded() {
+        assert_eq!(encode(&[300, 301, 302]), (3, vec![0b00_00_01, 44, 1, 1, 1]));
+    }
+
+    #[test]
+    fn blocks() {
+        for n in [1, 255, 256, 257, 1000, 1024] {
+            let values: Vec<u32> = (0..n).map(|x| x * 3 + 100).collect();
+            let (len, bytes) = encode(&values);
+            let mut blocks = DeltaBlocks::new(len, &bytes).unwrap();
+            let mut block = Vec::new();
+            let mut decoded = Vec::new();
+            while blocks.next_into(&mut block).unwrap() {
+                assert!(block.len() <= BLOCK_LEN);
+                decoded.extend_from_slice(&block);
+            }
+            assert_eq!(decoded, values);
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use crate::{
+    common::{control_bytes_len, StreamVbyteError},
+    tables::len::LENGTH_TABLE,
+};
+
+/// Sparse index into a delta-encoded stream of sorted values.
+///
+/// Stores the running value and the data offset every `interval` control
+/// bytes (i.e., every `4 * interval` values). [SkipIndex::lower_bound] uses it
+/// to jump close to the wanted position and then only decodes a single block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkipIndex {
+    interval: usize,
+    entries: Vec<SkipEntry>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct SkipEntry {
+    // The last value before the block, 0 for the first block.
+    prev: u32,
+    // Offset of the first data byte of the block, relative to the start of the
+    // data stream.
+    data_offset: usize,
+}
+
+impl SkipIndex {
+    /// Build the index for a delta-encoded stream by scanning it once.
+    ///
+    /// Panics if `interval` is 0.
+    pub fn build(len: usize, input: &[u8], interval: usize) -> Result<Self, StreamVbyteError> {
+        assert!(interval > 0, "skip interval must not be 0");
+        let mut entries = Vec::with_capacity(len / 4 / interval + 1);
+        if len == 0 {
+            return Ok(SkipIndex { interval, entries });
+        }
+        let (control, data) = split_streams(len, input)?;
+        let mut prev = 0u32;
+        let mut data_offset = 0;
+        // Only full control bytes can be skipped over.
+        for (i, &key) in control[..len / 4].iter().enumerate() {
+            if i.is_multiple_of(interval) {
+                entries.push(SkipEntry { prev, data_offset });
+            }
+            let next_offset = data_offset + LENGTH_TABLE[key as usize] as usize;
+            if next_offset > data.len() {
+                return Err(StreamVbyteError::DecodeOutOfBounds);
+            }
+            let mut ofs = data_offset;
+            for shift in [0, 2, 4, 6] {
+                let nbytes = ((key >> shift) & 0x3) as usize + 1;
+                prev = prev.wrapping_add(read_le(&data[ofs..ofs + nbytes]));
+                ofs += nbytes;
+            }
+            data_offset = next_offset;
+        }
+        if (len / 4).is_multiple_of(interval) {
+            entries.push(SkipEntry { prev, data_offset });
+        }
+        Ok(SkipIndex { interval, entries })
+    }
+
+    /// Build the index at encode time, from the values that were passed to
+    /// [super::encode] and the resulting bytes. Does not decode anything.
+    pub(crate) fn from_encoded(values: &[u32], encoded: &[u8], interval: usize) -> Self {
+        assert!(interval > 0, "skip interval must not be 0");
+        let len = values.len();
+        let mut entries = Vec::with_capacity(len / 4 / interval + 1);
+        let mut data_offset = 0;
+        for (i, &key) in encoded[..len / 4].iter().enumerate() {
+            if i.is_multiple_of(interval) {
+                let prev = if i == 0 { 0 } else { values[4 * i - 1] };
+                entries.push(SkipEntry { prev, data_offset });
+            }
+            data_offset += LENGTH_TABLE[key as usize] as usize;
+        }
+        if len > 0 && (len / 4).is_multiple_of(interval) {
+            let prev = if len < 4 { 0 } else { values[len / 4 * 4 - 1] };
+            entries.push(SkipEntry { prev, data_offset });
+        }
+        SkipIndex { interval, entries }
+    }
+
+    /// Number of control bytes between two index entries.
+    pub fn interval(&self) -> usize {
+        self.interval
+    }
+
+    /// Like [lower_bound], but uses the index to skip ahead. `len` and `input`
+    /// must be the stream the index was built for.
+    pub fn lower_bound(
+        &self,
+        len: usize,
+        input: &[u8],
+        target: u32,
+    ) -> Result<Option<(usize, u32)>, StreamVbyteError> {
+        if len == 0 {
+            return Ok(None);
+        }
+        // All values before block `j` are smaller than `target`, and, if block
+        // `j + 1` exists, its previous value is not. Therefore the result must
+        // be in block `j` (or not exist at all).
+        let j = self
+            .entries
+            .partition_point(|e| e.prev < target)
+            .saturating_sub(1);
+        let entry = self.entries.get(j).copied().unwrap_or_default();
+        let (control, data) = split_streams(len, input)?;
+        scan(
+            control,
+            data,
+            j * self.interval * 4..len,
+            entry.data_offset,
+            entry.prev,
+            target,
+        )
+    }
+
+    /// Like [contains], but uses the index to skip ahead.
+    pub fn contains(
+        &self,
+        len: usize,
+        input: &[u8],
+        target: u32,
+    ) -> Result<bool, StreamVbyteError> {
+        Ok(matches!(self.lower_bound(len, input, target)?, Some((_, v)) if v == target))
+    }
+}
+
+/// Find the first value `>= target` in a delta-encoded stream of sorted values.
+///
+/// Returns the index and value of that element, or `None` if all values are
+/// smaller than `target`. Decoding stops as soon as the element is found. For
+/// repeated lookups in long streams build a [SkipIndex] first.
+///
+/// ```
+/// let (len, bytes) = streamvb::delta::encode(&[3, 10, 200, 4000, 70000]);
+/// assert_eq!(streamvb::delta::lower_bound(len, &bytes, 11).unwrap(), Some((2, 200)));
+/// assert_eq!(streamvb::delta::lower_bound(len, &bytes, 70001).unwrap(), None);
+/// ```
+pub fn lower_bound(
+    len: usize,
+    input: &[u8],
+    target: u32,
+) -> Result<Option<(usize, u32)>, StreamVbyteError> {
+    if len == 0 {
+        return Ok(None);
+    }
+    let (control, data) = split_streams(len, input)?;
+    scan(control, data, 0..len, 0, 0, target)
+}
+
+/// Returns whether a delta-encoded stream of sorted values contains `target`.
+pub fn contains(len: usize, input: &[u8], target: u32) -> Result<bool, StreamVbyteError> {
+    Ok(matches!(lower_bound(len, input, target)?, Some((_, v)) if v == target))
+}
+
+fn split_streams(len: usize, input: &[u8]) -> Result<(&[u8], &[u8]), StreamVbyteError> {
+    let num_control_bytes = control_bytes_len(len);
+    if num_control_bytes >= input.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    Ok(input.split_at(num_control_bytes))
+}
+
+fn scan(
+    control: &[u8],
+    data: &[u8],
+    range: std::ops::Range<usize>,
+    mut data_offset: usize,
+    mut prev: u32,
+    target: u32,
+) -> Result<Option<(usize, u32)>, StreamVbyteError> {
+    for i in range {
+        let nbytes = ((control[i / 4] >> (2 * (i % 4))) & 0x3) as usize + 1;
+        let bytes = data
+            .get(data_offset..data_offset + nbytes)
+            .ok_or(StreamVbyteError::DecodeOutOfBounds)?;
+        prev = prev.wrapping_add(read_le(bytes));
+        data_offset += nbytes;
+        if prev >= target {
+            return Ok(Some((i, prev)));
+        }
+    }
+    Ok(None)
+}
+
+#[inline]
+fn read_le(bytes: &[u8]) -> u32 {
+    let mut buf = [0u8; 4];
+    buf[..bytes.len()].copy_from_slice(bytes);
+    u32::from_le_bytes(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{contains, lower_bound, SkipIndex};
+    use crate::delta::{encode, encode_with_index};
+
+    fn expected(values: &[u32], target: u32) -> Option<(usize, u32)> {
+        let i = values.partition_point(|&v| v < target);
+        values.get(i).map(|&v| (i, v))
+    }
+
+    #[test]
+    fn lower_bound_matches_partition_point() {
+        for n in [0, 1, 3, 4, 5, 63, 64, 65, 1000, 1003] {
+            let values: Vec<u32> = (0..n).map(|x| x * x / 3 + 2 * x).collect();
+            let (len, bytes) = encode(&values);
+            let built = SkipIndex::build(len, &bytes, 2).unwrap();
+            let (_, bytes2, from_encode) = encode_with_index(&values, 2);
+            assert_eq!(bytes, bytes2);
+            assert_eq!(built, from_encode);
+
+            let max = values.last().copied().unwrap_or(0);
+            for target in (0..max + 3).step_by(97).chain([0, 1, max, max + 1]) {
+                let want = expected(&values, target);
+                assert_eq!(lower_bound(len, &bytes, target).unwrap(), want);
+                assert_eq!(built.lower_bound(len, &bytes, target).unwrap(), want);
+                let found = values.binary_search(&target).is_ok();
+                assert_eq!(contains(len, &bytes, target).unwrap(), found);
+                assert_eq!(built.contains(len, &bytes, target).unwrap(), found);
+            }
+        }
+    }
+
+    #[test]
+    fn duplicates() {
+        let values = vec![1, 5, 5, 5, 5, 5, 5, 5, 5, 9, 9, 9];
+        let (len, bytes) = encode(&values);
+        let index = SkipIndex::build(len, &bytes, 1).unwrap();
+        assert_eq!(index.lower_bound(len, &bytes, 5).unwrap(), Some((1, 5)));
+        assert_eq!(index.lower_bound(len, &bytes, 6).unwrap(), Some((9, 9)));
+    }
+
+    #[test]
+    fn truncated_input() {
+        let values: Vec<u32> = (0..100).map(|x| x * 1000).collect();
+        let (len, bytes) = encode(&values);
+        let bytes = &bytes[..bytes.len() - 3];
+        assert!(lower_bound(len, bytes, u32::MAX).is_err());
+        assert!(SkipIndex::build(len, bytes, 4).is_err());
+    }
+}
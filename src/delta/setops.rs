@@ -0,0 +1,330 @@
+//! Set operations on delta-encoded sorted streams.
+//!
+//! The inputs are treated as sets, i.e., each stream must be strictly
+//! increasing. Streams are decoded block by block, so an intersection with a
+//! short list never needs to hold a long list in memory.
+use std::{cmp::Ordering, cmp::Reverse, collections::BinaryHeap};
+
+use super::{DeltaBlocks, BLOCK_LEN};
+use crate::common::StreamVbyteError;
+
+// If one list is this many times longer than the other, the intersection
+// gallops through the longer list instead of merging.
+const GALLOP_RATIO: usize = 32;
+
+/// Intersect two delta-encoded sorted streams, each given as `(len, bytes)`
+/// as returned by [super::encode].
+///
+/// ```
+/// use streamvb::delta;
+/// let (len_a, a) = delta::encode(&[1, 3, 5, 7, 9]);
+/// let (len_b, b) = delta::encode(&[3, 4, 5, 6]);
+/// assert_eq!(delta::intersect((len_a, &a), (len_b, &b)).unwrap(), vec![3, 5]);
+/// ```
+pub fn intersect(a: (usize, &[u8]), b: (usize, &[u8])) -> Result<Vec<u32>, StreamVbyteError> {
+    let mut output = Vec::with_capacity(a.0.min(b.0));
+    intersect_cursors(Cursor::new(a.0, a.1)?, Cursor::new(b.0, b.1)?, &mut output)?;
+    Ok(output)
+}
+
+/// Union of two delta-encoded sorted streams, each given as `(len, bytes)` as
+/// returned by [super::encode].
+///
+/// ```
+/// use streamvb::delta;
+/// let (len_a, a) = delta::encode(&[1, 3, 5]);
+/// let (len_b, b) = delta::encode(&[3, 4]);
+/// assert_eq!(delta::union((len_a, &a), (len_b, &b)).unwrap(), vec![1, 3, 4, 5]);
+/// ```
+pub fn union(a: (usize, &[u8]), b: (usize, &[u8])) -> Result<Vec<u32>, StreamVbyteError> {
+    let mut a = Cursor::new(a.0, a.1)?;
+    let mut b = Cursor::new(b.0, b.1)?;
+    let mut output = Vec::with_capacity(a.len.max(b.len));
+    while a.fill()? && b.fill()? {
+        let (i, j) = union_step(a.current(), b.current(), &mut output);
+        a.pos += i;
+        b.pos += j;
+    }
+    for rest in [&mut a, &mut b] {
+        while rest.fill()? {
+            output.extend_from_slice(rest.current());
+            rest.pos = rest.buf.len();
+        }
+    }
+    Ok(output)
+}
+
+/// Intersect any number of delta-encoded sorted streams.
+///
+/// The streams are intersected from shortest to longest, so the intermediate
+/// results stay small. Returns an empty vector if `lists` is empty.
+pub fn intersect_many(lists: &[(usize, &[u8])]) -> Result<Vec<u32>, StreamVbyteError> {
+    let mut order: Vec<usize> = (0..lists.len()).collect();
+    order.sort_by_key(|&k| lists[k].0);
+    let mut order = order.into_iter();
+
+    let mut result = match order.next() {
+        Some(k) => Cursor::new(lists[k].0, lists[k].1)?,
+        None => return Ok(Vec::new()),
+    };
+    let mut k = match order.next() {
+        Some(k) => k,
+        None => {
+            // A single list only needs decoding.
+            let mut output = Vec::with_capacity(result.len);
+            while result.fill()? {
+                output.extend_from_slice(result.current());
+                result.pos = result.buf.len();
+            }
+            return Ok(output);
+        }
+    };
+    loop {
+        let mut output = Vec::with_capacity(result.len.min(lists[k].0));
+        intersect_cursors(result, Cursor::new(lists[k].0, lists[k].1)?, &mut output)?;
+        match order.next() {
+            Some(next) if !output.is_empty() => {
+                result = Cursor::from_vec(output);
+                k = next;
+            }
+            _ => return Ok(output),
+        }
+    }
+}
+
+/// Union of any number of delta-encoded sorted streams, using a k-way merge.
+pub fn union_many(lists: &[(usize, &[u8])]) -> Result<Vec<u32>, StreamVbyteError> {
+    let mut cursors = lists
+        .iter()
+        .map(|&(len, input)| Cursor::new(len, input))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut heap = BinaryHeap::with_capacity(cursors.len());
+    for (k, cursor) in cursors.iter_mut().enumerate() {
+        if cursor.fill()? {
+            heap.push(Reverse((cursor.current()[0], k)));
+        }
+    }
+    let mut output = Vec::new();
+    while let Some(Reverse((value, k))) = heap.pop() {
+        if output.last() != Some(&value) {
+            output.push(value);
+        }
+        let cursor = &mut cursors[k];
+        cursor.pos += 1;
+        if cursor.fill()? {
+            heap.push(Reverse((cursor.current()[0], k)));
+        }
+    }
+    Ok(output)
+}
+
+// A position in a decoded block of a stream (or in an already decoded vector).
+struct Cursor<'a> {
+    blocks: DeltaBlocks<'a>,
+    buf: Vec<u32>,
+    pos: usize,
+    len: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(len: usize, input: &'a [u8]) -> Result<Self, StreamVbyteError> {
+        Ok(Cursor {
+            blocks: DeltaBlocks::new(len, input)?,
+            buf: Vec::with_capacity(BLOCK_LEN.min(len)),
+            pos: 0,
+            len,
+        })
+    }
+
+    fn from_vec(values: Vec<u32>) -> Self {
+        Cursor {
+            blocks: DeltaBlocks::empty(),
+            len: values.len(),
+            buf: values,
+            pos: 0,
+        }
+    }
+
+    fn current(&self) -> &[u32] {
+        &self.buf[self.pos..]
+    }
+
+    // Make sure `current()` is not empty. Returns `false` at the end of the
+    // stream.
+    fn fill(&mut self) -> Result<bool, StreamVbyteError> {
+        if self.pos < self.buf.len() {
+            return Ok(true);
+        }
+        self.pos = 0;
+        self.blocks.next_into(&mut self.buf)
+    }
+}
+
+fn intersect_cursors(a: Cursor, b: Cursor, output: &mut Vec<u32>) -> Result<(), StreamVbyteError> {
+    let (mut small, mut large) = if a.len <= b.len { (a, b) } else { (b, a) };
+    let gallop = small.len.saturating_mul(GALLOP_RATIO) < large.len;
+    while small.fill()? && large.fill()? {
+        let (i, j) = if gallop {
+            intersect_galloping(small.current(), large.current(), output)
+        } else {
+            intersect_step(small.current(), large.current(), output)
+        };
+        small.pos += i;
+        large.pos += j;
+    }
+    Ok(())
+}
+
+// Each of the `*_step` functions below processes the two slices until at least
+// one of them is exhausted and returns how many elements of each were consumed.
+
+fn intersect_step(a: &[u32], b: &[u32], output: &mut Vec<u32>) -> (usize, usize) {
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    ))]
+    let (i, j) = crate::x86_64::intersect::intersect_4x4(a, b, output);
+    #[cfg(not(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    )))]
+    let (i, j) = (0, 0);
+
+    let (di, dj) = intersect_scalar(&a[i..], &b[j..], output);
+    (i + di, j + dj)
+}
+
+fn intersect_scalar(a: &[u32], b: &[u32], output: &mut Vec<u32>) -> (usize, usize) {
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                output.push(a[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    (i, j)
+}
+
+// Looks up each element of `small` in `large` using exponential search.
+fn intersect_galloping(small: &[u32], large: &[u32], output: &mut Vec<u32>) -> (usize, usize) {
+    let mut j = 0;
+    for (i, &x) in small.iter().enumerate() {
+        let rest = &large[j..];
+        let mut bound = 1;
+        while bound < rest.len() && rest[bound] < x {
+            bound *= 2;
+        }
+        let lo = bound / 2;
+        let hi = (bound + 1).min(rest.len());
+        j += lo + rest[lo..hi].partition_point(|&v| v < x);
+        if j == large.len() {
+            return (i, j);
+        }
+        if large[j] == x {
+            output.push(x);
+            j += 1;
+        }
+    }
+    (small.len(), j)
+}
+
+fn union_step(a: &[u32], b: &[u32], output: &mut Vec<u32>) -> (usize, usize) {
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => {
+                output.push(a[i]);
+                i += 1;
+            }
+            Ordering::Greater => {
+                output.push(b[j]);
+                j += 1;
+            }
+            Ordering::Equal => {
+                output.push(a[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    (i, j)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use rand::Rng;
+
+    use super::{intersect, intersect_many, union, union_many};
+    use crate::delta::encode;
+
+    fn random_set(n: usize, max: u32) -> Vec<u32> {
+        let mut rng = rand::thread_rng();
+        let set: BTreeSet<u32> = (0..n).map(|_| rng.gen_range(0..max)).collect();
+        set.into_iter().collect()
+    }
+
+    #[test]
+    fn two_lists() {
+        let cases = [
+            (vec![], vec![1, 2, 3]),
+            (vec![1, 2, 3, 4, 5, 6, 7, 8], vec![2, 4, 6, 8, 10]),
+            (random_set(1000, 3000), random_set(1000, 3000)),
+            (random_set(5, 100_000), random_set(20_000, 100_000)),
+            (random_set(700, 1 << 30), random_set(600, 1 << 30)),
+        ];
+        for (a, b) in cases.iter() {
+            let set_a: BTreeSet<u32> = a.iter().copied().collect();
+            let set_b: BTreeSet<u32> = b.iter().copied().collect();
+            let (len_a, enc_a) = encode(a);
+            let (len_b, enc_b) = encode(b);
+
+            let want: Vec<u32> = set_a.intersection(&set_b).copied().collect();
+            assert_eq!(intersect((len_a, &enc_a), (len_b, &enc_b)).unwrap(), want);
+            assert_eq!(intersect((len_b, &enc_b), (len_a, &enc_a)).unwrap(), want);
+
+            let want: Vec<u32> = set_a.union(&set_b).copied().collect();
+            assert_eq!(union((len_a, &enc_a), (len_b, &enc_b)).unwrap(), want);
+        }
+    }
+
+    #[test]
+    fn many_lists() {
+        let lists = [
+            random_set(2000, 4000),
+            random_set(3000, 4000),
+            random_set(500, 4000),
+            random_set(2500, 4000),
+        ];
+        let encoded: Vec<(usize, Vec<u8>)> = lists.iter().map(|l| encode(l)).collect();
+        let refs: Vec<(usize, &[u8])> = encoded.iter().map(|(n, b)| (*n, &b[..])).collect();
+
+        let sets: Vec<BTreeSet<u32>> = lists.iter().map(|l| l.iter().copied().collect()).collect();
+        let mut want_and = sets[0].clone();
+        let mut want_or: BTreeSet<u32> = BTreeSet::new();
+        for set in &sets {
+            want_and = want_and.intersection(set).copied().collect();
+            want_or.extend(set);
+        }
+        assert_eq!(
+            intersect_many(&refs).unwrap(),
+            want_and.into_iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            union_many(&refs).unwrap(),
+            want_or.into_iter().collect::<Vec<_>>()
+        );
+
+        assert_eq!(intersect_many(&[]).unwrap(), Vec::<u32>::new());
+        assert_eq!(intersect_many(&refs[..1]).unwrap(), lists[0]);
+        assert_eq!(union_many(&refs[..1]).unwrap(), lists[0]);
+    }
+}
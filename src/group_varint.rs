@@ -0,0 +1,232 @@
+//! Group Varint, the predecessor of StreamVByte.
+//!
+//! Values are grouped by four. Every group starts with a tag byte that holds
+//! the 2-bit codes of its values, exactly like a StreamVByte control byte,
+//! followed by the data bytes of the group. A final group of fewer than four
+//! values has zeros in the unused bits of its tag and only the data bytes of
+//! the values present.
+//!
+//! The total size is the same as with StreamVByte, only the control bytes are
+//! interleaved with the data. [to_streamvbyte] converts between the two
+//! without decoding the values.
+use crate::common::{control_bytes_len, encode_one, max_compressed_len, StreamVbyteError};
+use crate::tables::len::LENGTH_TABLE;
+
+/// Encode a slice of `u32` values in Group Varint format.
+///
+/// ```
+/// let (len, bytes) = streamvb::group_varint::encode(&[1, 300, 70000, 0xdeadbeef, 2]);
+/// assert_eq!(
+///     bytes,
+///     vec![0b11_10_01_00, 1, 44, 1, 0x70, 0x11, 0x01, 0xef, 0xbe, 0xad, 0xde, 0, 2]
+/// );
+/// assert_eq!(streamvb::group_varint::decode(len, &bytes).unwrap(), [1, 300, 70000, 0xdeadbeef, 2]);
+/// ```
+pub fn encode(values: &[u32]) -> (usize, Vec<u8>) {
+    let mut output = Vec::with_capacity(max_compressed_len(values.len()));
+    for group in values.chunks(4) {
+        let tag = output.len();
+        output.push(0);
+        for (i, &value) in group.iter().enumerate() {
+            let code = encode_one(value);
+            output[tag] |= (code << (2 * i)) as u8;
+            output.extend_from_slice(&value.to_le_bytes()[..code as usize + 1]);
+        }
+    }
+    (values.len(), output)
+}
+
+/// Decode `len` values encoded using [encode].
+///
+/// Bytes after the last group are ignored. Returns an error if the decoding
+/// process tried to read bytes outside of the input slice.
+pub fn decode(len: usize, input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
+    check_len(len, input)?;
+    let mut output = Vec::with_capacity(len);
+    let mut offset = decode_groups_simd(len / 4, input, &mut output);
+    while output.len() < len {
+        let tag = *input
+            .get(offset)
+            .ok_or(StreamVbyteError::DecodeOutOfBounds)?;
+        offset += 1;
+        for i in 0..(len - output.len()).min(4) {
+            let width = ((tag >> (2 * i)) & 0x3) as usize + 1;
+            let bytes = input
+                .get(offset..offset + width)
+                .ok_or(StreamVbyteError::DecodeOutOfBounds)?;
+            let mut buf = [0u8; 4];
+            buf[..width].copy_from_slice(bytes);
+            output.push(u32::from_le_bytes(buf));
+            offset += width;
+        }
+    }
+    Ok(output)
+}
+
+/// Convert `len` values encoded using [encode] into the format of
+/// [crate::encode].
+///
+/// Only moves the tag bytes into a separate control stream, the values are
+/// not decoded.
+///
+/// ```
+/// let values = [1, 300, 70000, 0xdeadbeef, 2];
+/// let (len, bytes) = streamvb::group_varint::encode(&values);
+/// let converted = streamvb::group_varint::to_streamvbyte(len, &bytes).unwrap();
+/// assert_eq!(converted, streamvb::encode(&values).1);
+/// ```
+pub fn to_streamvbyte(len: usize, input: &[u8]) -> Result<Vec<u8>, StreamVbyteError> {
+    check_len(len, input)?;
+    // First find the groups, then copy them, so that the output is allocated
+    // only once and with the exact size.
+    let num_controls = control_bytes_len(len);
+    let mut data_len = 0;
+    let mut offset = 0;
+    for group in 0..num_controls {
+        let tag = *input
+            .get(offset)
+            .ok_or(StreamVbyteError::DecodeOutOfBounds)?;
+        let width = group_data_len(tag, (len - 4 * group).min(4));
+        offset += 1 + width;
+        data_len += width;
+    }
+    if offset > input.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+
+    let mut output = vec![0; num_controls + data_len];
+    let (control, data) = output.split_at_mut(num_controls);
+    let mut offset = 0;
+    let mut data_offset = 0;
+    for (group, control) in control.iter_mut().enumerate() {
+        let count = (len - 4 * group).min(4);
+        // Drop whatever is in the unused bits of a final partial tag.
+        let tag = input[offset] & (0xff >> (8 - 2 * count));
+        let width = group_data_len(tag, count);
+        *control = tag;
+        data[data_offset..data_offset + width]
+            .copy_from_slice(&input[offset + 1..offset + 1 + width]);
+        offset += 1 + width;
+        data_offset += width;
+    }
+    Ok(output)
+}
+
+// Every value takes at least one data byte and a quarter tag byte. Checked
+// before allocating, so a bogus `len` cannot trigger a huge allocation.
+fn check_len(len: usize, input: &[u8]) -> Result<(), StreamVbyteError> {
+    match len.checked_add(control_bytes_len(len)) {
+        Some(min_len) if min_len <= input.len() => Ok(()),
+        _ => Err(StreamVbyteError::DecodeOutOfBounds),
+    }
+}
+
+// Number of data bytes of the first `count` values of a group.
+fn group_data_len(tag: u8, count: usize) -> usize {
+    if count == 4 {
+        return LENGTH_TABLE[tag as usize] as usize;
+    }
+    (0..count)
+        .map(|i| ((tag >> (2 * i)) & 0x3) as usize + 1)
+        .sum()
+}
+
+// Decodes up to `num_groups` full groups, stopping early when fewer than 16
+// data bytes follow the next tag. Appends the values to `output`, which must
+// be empty and have room for them, and returns the number of input bytes
+// consumed.
+#[cfg(any(
+    all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ),
+    all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    )
+))]
+fn decode_groups_simd(num_groups: usize, input: &[u8], output: &mut Vec<u32>) -> usize {
+    #[cfg(target_arch = "aarch64")]
+    use crate::aarch64::decode::{step_simd, NoDecode};
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    use crate::x86_64::decode::{step_simd, NoDecode};
+
+    debug_assert!(output.is_empty() && output.capacity() >= 4 * num_groups);
+    let end: *const u8 = input.as_ptr_range().end;
+    let mut data_ptr: *const u8 = input.as_ptr();
+    let mut done = 0;
+    // Safety: `step_simd` reads 16 bytes after the tag, and writes 4 values
+    // into the room reserved by the caller.
+    unsafe {
+        let out: *mut u32 = output.as_mut_ptr();
+        while done < num_groups && end.offset_from(data_ptr) > 16 {
+            let tag = *data_ptr;
+            data_ptr = step_simd(&mut NoDecode, tag, data_ptr.add(1), out.add(4 * done));
+            done += 1;
+        }
+        output.set_len(4 * done);
+        data_ptr.offset_from(input.as_ptr()) as usize
+    }
+}
+
+// Without SIMD, [decode] decodes every group itself.
+#[cfg(not(any(
+    all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ),
+    all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    )
+)))]
+fn decode_groups_simd(_num_groups: usize, _input: &[u8], _output: &mut Vec<u32>) -> usize {
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode, to_streamvbyte};
+
+    fn sample_inputs() -> Vec<Vec<u32>> {
+        crate::common::sample_inputs(&[255, 256, 65535, 65536, 0xffffff, 0x1000000, u32::MAX])
+    }
+
+    #[test]
+    fn encode_decode() {
+        for input in sample_inputs() {
+            let (len, bytes) = encode(&input);
+            assert_eq!(bytes.len(), crate::encode(&input).1.len());
+            assert_eq!(decode(len, &bytes).unwrap(), input);
+            for end in 0..bytes.len() {
+                assert!(decode(len, &bytes[..end]).is_err());
+                assert!(to_streamvbyte(len, &bytes[..end]).is_err());
+            }
+            assert!(decode(len + 1, &bytes).is_err());
+        }
+        assert!(decode(usize::MAX, &[0; 10]).is_err());
+    }
+
+    #[test]
+    fn convert() {
+        for input in sample_inputs() {
+            let (len, bytes) = encode(&input);
+            assert_eq!(
+                to_streamvbyte(len, &bytes).unwrap(),
+                crate::encode(&input).1
+            );
+        }
+        // Garbage in the unused bits of the last tag is not copied.
+        let (len, mut bytes) = encode(&[1, 2, 3, 4, 5]);
+        bytes[5] |= 0b1111_1100;
+        assert_eq!(
+            to_streamvbyte(len, &bytes).unwrap(),
+            crate::encode(&[1, 2, 3, 4, 5]).1
+        );
+        assert_eq!(decode(len, &bytes).unwrap(), vec![1, 2, 3, 4, 5]);
+    }
+}
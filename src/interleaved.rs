@@ -0,0 +1,473 @@
+//! A layout with the control bytes next to their data, for single-pass
+//! streaming.
+//!
+//! With [crate::encode], all control bytes come first, so nothing can be
+//! written before the number of values is known. The interleaved layout
+//! instead splits the values into blocks of up to [BLOCK_LEN] values:
+//!
+//! ```text
+//! [len: u8] [control bytes of the block] [data bytes of the block]
+//! ...
+//! [0]
+//! ```
+//!
+//! Each block uses the layout of [crate::encode] and is encoded and decoded
+//! with the same SIMD kernels. Full blocks hold [BLOCK_LEN] values. Shorter
+//! blocks may appear anywhere, e.g. where a writer flushed. A zero length
+//! ends the stream. The length bytes add one byte per block.
+//!
+//! [to_classic] and [from_classic] convert between the two layouts without
+//! decoding the values.
+use std::io::{self, Read, Write};
+
+use crate::common::{control_bytes_len, data_bytes_len, StreamVbyteError};
+use crate::io::Sink;
+
+/// Number of values in a full block.
+pub const BLOCK_LEN: usize = 64;
+
+/// Encode a slice of `u32` values in the interleaved layout, including the
+/// terminator.
+///
+/// ```
+/// let values: Vec<u32> = (0..100).collect();
+/// let bytes = streamvb::interleaved::encode(&values);
+/// assert_eq!(bytes.len(), 1 + 16 + 64 + 1 + 9 + 36 + 1);
+/// assert_eq!(streamvb::interleaved::decode(&bytes).unwrap(), values);
+/// ```
+pub fn encode(values: &[u32]) -> Vec<u8> {
+    let mut output = Vec::new();
+    for block in values.chunks(BLOCK_LEN) {
+        encode_block(block, &mut output);
+    }
+    output.push(0);
+    output
+}
+
+/// Decode all values of an interleaved stream. Bytes after the terminator are
+/// ignored.
+///
+/// Returns an error if the stream ends before the terminator or a block
+/// header is invalid.
+pub fn decode(input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
+    let mut output = Vec::new();
+    let mut input = input;
+    while let Some(block) = split_block(input)? {
+        crate::decode_parts_into(block.len, block.control, block.tail, &mut output)?;
+        input = &block.tail[block.data_len..];
+    }
+    Ok(output)
+}
+
+/// Convert an interleaved stream into the layout of [crate::encode]. Returns
+/// the number of values and the encoded bytes, like [crate::encode].
+///
+/// ```
+/// let values: Vec<u32> = (0..100).map(|x| x * 1000).collect();
+/// let bytes = streamvb::interleaved::encode(&values);
+/// assert_eq!(streamvb::interleaved::to_classic(&bytes).unwrap(), streamvb::encode(&values));
+/// ```
+pub fn to_classic(input: &[u8]) -> Result<(usize, Vec<u8>), StreamVbyteError> {
+    let mut control = Vec::new();
+    let mut data = Vec::new();
+    let mut len = 0;
+    let mut input = input;
+    while let Some(block) = split_block(input)? {
+        if len % 4 == 0 {
+            control.extend_from_slice(block.control);
+            // Drop whatever is in the unused bits of a partial control byte.
+            if block.len % 4 != 0 {
+                *control.last_mut().unwrap() &= 0xff >> (8 - 2 * (block.len % 4));
+            }
+        } else {
+            // After a block that ended mid control byte, the 2-bit codes have
+            // to be moved one at a time.
+            for i in 0..block.len {
+                let code = (block.control[i / 4] >> (2 * (i % 4))) & 0x3;
+                let j = len + i;
+                if j % 4 == 0 {
+                    control.push(0);
+                }
+                *control.last_mut().unwrap() |= code << (2 * (j % 4));
+            }
+        }
+        data.extend_from_slice(&block.tail[..block.data_len]);
+        len += block.len;
+        input = &block.tail[block.data_len..];
+    }
+    control.extend_from_slice(&data);
+    Ok((len, control))
+}
+
+/// Convert `len` values encoded using [crate::encode] into the interleaved
+/// layout.
+///
+/// ```
+/// let values: Vec<u32> = (0..100).map(|x| x * 1000).collect();
+/// let (len, bytes) = streamvb::encode(&values);
+/// let converted = streamvb::interleaved::from_classic(len, &bytes).unwrap();
+/// assert_eq!(converted, streamvb::interleaved::encode(&values));
+/// ```
+pub fn from_classic(len: usize, input: &[u8]) -> Result<Vec<u8>, StreamVbyteError> {
+    let num_controls = control_bytes_len(len);
+    if num_controls > input.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    let (control, mut data) = input.split_at(num_controls);
+    let num_blocks = len.div_ceil(BLOCK_LEN);
+    let mut output = Vec::with_capacity(input.len() + num_blocks + 1);
+    for (block, control) in control.chunks(BLOCK_LEN / 4).enumerate() {
+        let block_len = (len - block * BLOCK_LEN).min(BLOCK_LEN);
+        let data_len = data_bytes_len(control, block_len);
+        if data_len > data.len() {
+            return Err(StreamVbyteError::DecodeOutOfBounds);
+        }
+        output.push(block_len as u8);
+        output.extend_from_slice(control);
+        output.extend_from_slice(&data[..data_len]);
+        data = &data[data_len..];
+    }
+    output.push(0);
+    Ok(output)
+}
+
+/// Writes batches of `u32` values in the interleaved layout.
+///
+/// Full blocks are written as soon as they are complete, so at most
+/// [BLOCK_LEN] values are buffered. [flush][InterleavedWriter::flush] writes
+/// the buffered values as a short block. Only
+/// [finish][InterleavedWriter::finish] writes the terminator. Dropping the
+/// writer writes out the buffered values, ignoring any errors, but leaves the
+/// stream without a terminator, so readers report it as truncated.
+///
+/// After a write to the underlying writer failed, the stream is left without
+/// a terminator, and all further calls return an error instead of writing
+/// more blocks after it.
+///
+/// ```
+/// use streamvb::interleaved::{InterleavedReader, InterleavedWriter};
+/// let mut writer = InterleavedWriter::new(Vec::new());
+/// writer.write_values(&[1, 2, 3]).unwrap();
+/// writer.write_values(&[70000]).unwrap();
+/// let file = writer.finish().unwrap();
+///
+/// let mut reader = InterleavedReader::new(&file[..]);
+/// let mut batch = Vec::new();
+/// assert!(reader.read_batch(&mut batch).unwrap());
+/// assert_eq!(batch, vec![1, 2, 3, 70000]);
+/// assert!(!reader.read_batch(&mut batch).unwrap());
+/// ```
+pub struct InterleavedWriter<W: Write> {
+    inner: Sink<W>,
+    pending: Vec<u32>,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> InterleavedWriter<W> {
+    pub fn new(inner: W) -> Self {
+        InterleavedWriter {
+            inner: Sink::new(inner),
+            pending: Vec::with_capacity(BLOCK_LEN),
+            buf: Vec::new(),
+        }
+    }
+
+    /// Append `values` to the stream, writing out every block that fills up.
+    pub fn write_values(&mut self, mut values: &[u32]) -> io::Result<()> {
+        self.inner.check()?;
+        self.buf.clear();
+        if !self.pending.is_empty() {
+            let take = (BLOCK_LEN - self.pending.len()).min(values.len());
+            self.pending.extend_from_slice(&values[..take]);
+            values = &values[take..];
+            if self.pending.len() < BLOCK_LEN {
+                return Ok(());
+            }
+            encode_block(&self.pending, &mut self.buf);
+        }
+        // Encode full blocks straight from `values`.
+        let full = values.len() - values.len() % BLOCK_LEN;
+        for block in values[..full].chunks(BLOCK_LEN) {
+            encode_block(block, &mut self.buf);
+        }
+        self.inner.write_all(&self.buf)?;
+        self.pending.clear();
+        self.pending.extend_from_slice(&values[full..]);
+        Ok(())
+    }
+
+    /// Write out buffered values as a short block and flush the underlying
+    /// writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.write_pending()?;
+        self.inner.flush()
+    }
+
+    /// Write out buffered values and the terminator, flush, and return the
+    /// underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_pending()?;
+        self.inner.write_all(&[0])?;
+        self.inner.flush()?;
+        Ok(self.inner.take())
+    }
+
+    pub fn get_ref(&self) -> &W {
+        self.inner.get_ref()
+    }
+
+    fn write_pending(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        self.buf.clear();
+        encode_block(&self.pending, &mut self.buf);
+        self.inner.write_all(&self.buf)?;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+impl<W: Write> Drop for InterleavedWriter<W> {
+    fn drop(&mut self) {
+        if self.inner.is_writable() {
+            let _ = self.write_pending();
+        }
+    }
+}
+
+/// Reads a stream written by [InterleavedWriter] or [encode] one block at a
+/// time.
+///
+/// Every block is read with up to three calls to the underlying reader, so
+/// wrap unbuffered sources in a [std::io::BufReader]. Input that ends before
+/// the terminator yields an [io::ErrorKind::UnexpectedEof] error, and
+/// malformed blocks an [io::ErrorKind::InvalidData] error.
+pub struct InterleavedReader<R: Read> {
+    inner: R,
+    buf: Vec<u8>,
+    finished: bool,
+}
+
+impl<R: Read> InterleavedReader<R> {
+    pub fn new(inner: R) -> Self {
+        InterleavedReader {
+            inner,
+            buf: Vec::new(),
+            finished: false,
+        }
+    }
+
+    /// Decode the next block into `output`, replacing its contents. Returns
+    /// `false` (with `output` empty) after the terminator.
+    pub fn read_batch(&mut self, output: &mut Vec<u32>) -> io::Result<bool> {
+        output.clear();
+        if self.finished {
+            return Ok(false);
+        }
+        let mut header = [0u8; 1];
+        self.inner.read_exact(&mut header)?;
+        let len = header[0] as usize;
+        if len == 0 {
+            self.finished = true;
+            return Ok(false);
+        }
+        if len > BLOCK_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                StreamVbyteError::InvalidBlockHeader,
+            ));
+        }
+        let num_controls = control_bytes_len(len);
+        self.buf.resize(num_controls, 0);
+        self.inner.read_exact(&mut self.buf)?;
+        let data_len = data_bytes_len(&self.buf, len);
+        self.buf.resize(num_controls + data_len, 0);
+        self.inner.read_exact(&mut self.buf[num_controls..])?;
+        let (control, data) = self.buf.split_at(num_controls);
+        crate::decode_parts_into(len, control, data, output)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(true)
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+fn encode_block(values: &[u32], output: &mut Vec<u8>) {
+    output.push(values.len() as u8);
+    crate::encode_into(values, output);
+}
+
+// A block at the start of some input.
+struct Block<'a> {
+    len: usize,
+    control: &'a [u8],
+    // The data bytes of the block and everything after them.
+    tail: &'a [u8],
+    data_len: usize,
+}
+
+// Returns `None` at the terminator.
+fn split_block(input: &[u8]) -> Result<Option<Block<'_>>, StreamVbyteError> {
+    let (&len, rest) = input
+        .split_first()
+        .ok_or(StreamVbyteError::DecodeOutOfBounds)?;
+    let len = len as usize;
+    if len == 0 {
+        return Ok(None);
+    }
+    if len > BLOCK_LEN {
+        return Err(StreamVbyteError::InvalidBlockHeader);
+    }
+    let num_controls = control_bytes_len(len);
+    if num_controls > rest.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    let (control, tail) = rest.split_at(num_controls);
+    let data_len = data_bytes_len(control, len);
+    if data_len > tail.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    Ok(Some(Block {
+        len,
+        control,
+        tail,
+        data_len,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{ErrorKind, Write};
+
+    use super::*;
+    use crate::common::sample_values;
+
+    #[test]
+    fn encode_decode() {
+        for n in [0, 1, 63, 64, 65, 128, 1003] {
+            let values = sample_values(n);
+            let bytes = encode(&values);
+            assert_eq!(decode(&bytes).unwrap(), values);
+            assert_eq!(to_classic(&bytes).unwrap(), crate::encode(&values));
+            let (len, classic) = crate::encode(&values);
+            assert_eq!(from_classic(len, &classic).unwrap(), bytes);
+            for end in 0..bytes.len() {
+                assert!(decode(&bytes[..end]).is_err());
+                assert!(to_classic(&bytes[..end]).is_err());
+            }
+            if len > 0 {
+                assert!(from_classic(len, &classic[..classic.len() - 1]).is_err());
+            }
+        }
+        assert!(matches!(
+            decode(&[65]),
+            Err(StreamVbyteError::InvalidBlockHeader)
+        ));
+    }
+
+    #[test]
+    fn streaming() {
+        let values = sample_values(1000);
+        let mut writer = InterleavedWriter::new(Vec::new());
+        let mut start = 0;
+        for (i, batch) in [3, 0, 61, 64, 1, 130, 200, 541].iter().enumerate() {
+            writer.write_values(&values[start..start + batch]).unwrap();
+            start += batch;
+            if i % 3 == 0 {
+                // Leaves short blocks in the middle of the stream.
+                writer.flush().unwrap();
+            }
+        }
+        let file = writer.finish().unwrap();
+        assert_eq!(decode(&file).unwrap(), values);
+        assert_eq!(to_classic(&file).unwrap(), crate::encode(&values));
+
+        let mut reader = InterleavedReader::new(&file[..]);
+        let mut batch = Vec::new();
+        let mut result = Vec::new();
+        while reader.read_batch(&mut batch).unwrap() {
+            assert!(batch.len() <= BLOCK_LEN);
+            result.extend_from_slice(&batch);
+        }
+        assert_eq!(result, values);
+
+        let mut reader = InterleavedReader::new(&file[..file.len() - 1]);
+        let err = loop {
+            match reader.read_batch(&mut batch) {
+                Ok(true) => {}
+                Ok(false) => panic!("missing terminator not detected"),
+                Err(e) => break e,
+            }
+        };
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn drop_does_not_finish() {
+        let mut file = Vec::new();
+        {
+            let mut writer = InterleavedWriter::new(&mut file);
+            writer.write_values(&[1, 2, 3]).unwrap();
+        }
+        // The values were written, but without a terminator.
+        assert_eq!(file, encode(&[1, 2, 3])[..file.len()]);
+        assert!(decode(&file).is_err());
+    }
+
+    // Accepts `limit` bytes, then fails every write.
+    struct FailingWriter {
+        written: Vec<u8>,
+        limit: usize,
+    }
+
+    impl Write for FailingWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.limit - self.written.len());
+            if n == 0 && !buf.is_empty() {
+                return Err(ErrorKind::WriteZero.into());
+            }
+            self.written.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn failed_write_poisons() {
+        let values = sample_values(100);
+        let mut sink = FailingWriter {
+            written: Vec::new(),
+            limit: 20,
+        };
+        {
+            let mut writer = InterleavedWriter::new(&mut sink);
+            writer.write_values(&values[..10]).unwrap();
+            assert!(writer.write_values(&values[10..]).is_err());
+            writer.inner.get_mut().limit = usize::MAX;
+            assert!(writer.write_values(&[1]).is_err());
+            assert!(writer.flush().is_err());
+        }
+        // The drop did not make the truncated stream look complete.
+        assert_eq!(sink.written.len(), 20);
+        assert!(decode(&sink.written).is_err());
+
+        let mut sink = FailingWriter {
+            written: Vec::new(),
+            limit: 5,
+        };
+        let mut writer = InterleavedWriter::new(&mut sink);
+        writer.write_values(&values[..10]).unwrap();
+        assert!(writer.finish().is_err());
+        assert_eq!(sink.written.len(), 5);
+    }
+}
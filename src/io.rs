@@ -0,0 +1,397 @@
+//! Adapters for writing encoded integer streams to [Write] and reading them
+//! back from [Read].
+//!
+//! The stream is a sequence of self-contained chunks. Each chunk is framed as
+//!
+//! ```text
+//! [count: u32 LE] [nbytes: u32 LE] [nbytes bytes of StreamVByte encoding]
+//! ```
+//!
+//! where the encoded bytes use the same layout as [crate::encode]. Both sides
+//! hold at most one chunk in memory.
+use std::io::{self, Read, Write};
+
+use crate::common::{control_bytes_len, data_bytes_len, max_compressed_len};
+
+/// Default number of values per chunk.
+pub const DEFAULT_CHUNK_LEN: usize = 1 << 16;
+
+const HEADER_LEN: usize = 8;
+
+/// Writes batches of `u32` values as framed, encoded chunks.
+///
+/// Values are buffered until a full chunk is available. Call
+/// [flush][StreamVByteWriter::flush] or [finish][StreamVByteWriter::finish] to
+/// write out the last partial chunk. Dropping the writer flushes it too, but
+/// ignores any errors.
+///
+/// After a write to the underlying writer failed, the stream ends with a
+/// partial chunk, and all further calls return an error instead of writing
+/// more chunks after it.
+///
+/// ```
+/// use streamvb::io::{StreamVByteReader, StreamVByteWriter};
+/// let mut writer = StreamVByteWriter::new(Vec::new());
+/// writer.write_values(&[1, 2, 3]).unwrap();
+/// writer.write_values(&[70000]).unwrap();
+/// let file = writer.finish().unwrap();
+///
+/// let mut reader = StreamVByteReader::new(&file[..]);
+/// let mut batch = Vec::new();
+/// assert!(reader.read_batch(&mut batch).unwrap());
+/// assert_eq!(batch, vec![1, 2, 3, 70000]);
+/// assert!(!reader.read_batch(&mut batch).unwrap());
+/// ```
+pub struct StreamVByteWriter<W: Write> {
+    inner: Sink<W>,
+    pending: Vec<u32>,
+    // The encoding of the chunk being written, reused across chunks.
+    buf: Vec<u8>,
+    chunk_len: usize,
+}
+
+impl<W: Write> StreamVByteWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self::with_chunk_len(inner, DEFAULT_CHUNK_LEN)
+    }
+
+    /// Create a writer that emits chunks of up to `chunk_len` values.
+    ///
+    /// Panics if `chunk_len` is zero or does not fit into the `u32` frame
+    /// header.
+    pub fn with_chunk_len(inner: W, chunk_len: usize) -> Self {
+        assert!(chunk_len > 0, "chunk_len must not be zero");
+        assert!(
+            max_compressed_len(chunk_len) <= u32::MAX as usize,
+            "chunk_len too large"
+        );
+        StreamVByteWriter {
+            inner: Sink::new(inner),
+            pending: Vec::with_capacity(chunk_len),
+            buf: Vec::new(),
+            chunk_len,
+        }
+    }
+
+    /// Append `values` to the stream, writing out every chunk that fills up.
+    pub fn write_values(&mut self, mut values: &[u32]) -> io::Result<()> {
+        self.inner.check()?;
+        while !values.is_empty() {
+            let take = (self.chunk_len - self.pending.len()).min(values.len());
+            self.pending.extend_from_slice(&values[..take]);
+            values = &values[take..];
+            if self.pending.len() == self.chunk_len {
+                self.write_chunk()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write out buffered values as a (possibly short) chunk and flush the
+    /// underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.write_chunk()?;
+        self.inner.flush()
+    }
+
+    /// Flush and return the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.flush()?;
+        Ok(self.inner.take())
+    }
+
+    pub fn get_ref(&self) -> &W {
+        self.inner.get_ref()
+    }
+
+    fn write_chunk(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        self.buf.clear();
+        crate::encode_into(&self.pending, &mut self.buf);
+        let mut header = [0u8; HEADER_LEN];
+        header[..4].copy_from_slice(&(self.pending.len() as u32).to_le_bytes());
+        header[4..].copy_from_slice(&(self.buf.len() as u32).to_le_bytes());
+        self.inner.write_all(&header)?;
+        self.inner.write_all(&self.buf)?;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+impl<W: Write> Drop for StreamVByteWriter<W> {
+    fn drop(&mut self) {
+        if self.inner.is_writable() {
+            let _ = self.write_chunk();
+        }
+    }
+}
+
+/// The underlying writer of an encoding writer.
+///
+/// A failed write leaves a partial chunk or block at the end of the output.
+/// After that, all calls return an error instead of writing more after it.
+pub(crate) struct Sink<W: Write> {
+    // `None` only after `take` took it out.
+    inner: Option<W>,
+    // Set when writing to `inner` failed.
+    poisoned: bool,
+}
+
+impl<W: Write> Sink<W> {
+    pub(crate) fn new(inner: W) -> Self {
+        Sink {
+            inner: Some(inner),
+            poisoned: false,
+        }
+    }
+
+    /// Returns an error if an earlier write failed.
+    pub(crate) fn check(&self) -> io::Result<()> {
+        if self.poisoned {
+            return Err(io::Error::other(
+                "an earlier write to the underlying writer failed",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether writes can still succeed, for use in `Drop`.
+    pub(crate) fn is_writable(&self) -> bool {
+        self.inner.is_some() && !self.poisoned
+    }
+
+    pub(crate) fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        let result = self.inner_mut()?.write_all(buf);
+        self.poison_on_error(result)
+    }
+
+    pub(crate) fn flush(&mut self) -> io::Result<()> {
+        let result = self.inner_mut()?.flush();
+        self.poison_on_error(result)
+    }
+
+    pub(crate) fn get_ref(&self) -> &W {
+        self.inner.as_ref().expect("writer already finished")
+    }
+
+    #[cfg(test)]
+    pub(crate) fn get_mut(&mut self) -> &mut W {
+        self.inner.as_mut().expect("writer already finished")
+    }
+
+    pub(crate) fn take(&mut self) -> W {
+        self.inner.take().expect("writer already finished")
+    }
+
+    fn inner_mut(&mut self) -> io::Result<&mut W> {
+        self.check()?;
+        Ok(self.inner.as_mut().expect("writer already finished"))
+    }
+
+    fn poison_on_error(&mut self, result: io::Result<()>) -> io::Result<()> {
+        if result.is_err() {
+            self.poisoned = true;
+        }
+        result
+    }
+}
+
+/// Reads chunks written by [StreamVByteWriter] and decodes them into batches.
+///
+/// Chunks claiming more than [max_chunk_len][StreamVByteReader::with_max_chunk_len]
+/// values are rejected before anything is allocated for them. Input that ends
+/// in the middle of a chunk yields an [io::ErrorKind::UnexpectedEof] error,
+/// and malformed chunks an [io::ErrorKind::InvalidData] error.
+pub struct StreamVByteReader<R: Read> {
+    inner: R,
+    buf: Vec<u8>,
+    max_chunk_len: usize,
+}
+
+impl<R: Read> StreamVByteReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self::with_max_chunk_len(inner, DEFAULT_CHUNK_LEN)
+    }
+
+    /// Create a reader that rejects chunks of more than `max_chunk_len`
+    /// values. This bounds the memory used per batch.
+    pub fn with_max_chunk_len(inner: R, max_chunk_len: usize) -> Self {
+        StreamVByteReader {
+            inner,
+            buf: Vec::new(),
+            max_chunk_len,
+        }
+    }
+
+    /// Decode the next chunk into `output`, replacing its contents. Returns
+    /// `false` (with `output` empty) at the end of the stream.
+    pub fn read_batch(&mut self, output: &mut Vec<u32>) -> io::Result<bool> {
+        output.clear();
+        let mut header = [0u8; HEADER_LEN];
+        match read_full(&mut self.inner, &mut header)? {
+            0 => return Ok(false),
+            HEADER_LEN => {}
+            _ => return Err(eof("truncated chunk header")),
+        }
+        let count = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let nbytes = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        if count > self.max_chunk_len {
+            return Err(invalid("chunk has more values than allowed"));
+        }
+        if nbytes > max_compressed_len(count) || nbytes < control_bytes_len(count) {
+            return Err(invalid("chunk size does not match its value count"));
+        }
+
+        self.buf.clear();
+        self.buf.resize(nbytes, 0);
+        if read_full(&mut self.inner, &mut self.buf)? != nbytes {
+            return Err(eof("truncated chunk data"));
+        }
+        let (control, data) = self.buf.split_at(control_bytes_len(count));
+        if data_bytes_len(control, count) != data.len() {
+            return Err(invalid("chunk size does not match its control bytes"));
+        }
+        crate::decode_parts_into(count, control, data, output)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(true)
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+// Like `read_exact`, but returns the number of bytes read before EOF instead
+// of failing.
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+fn eof(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, message)
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{ErrorKind, Write};
+
+    use super::{StreamVByteReader, StreamVByteWriter};
+
+    fn write_all(batches: &[&[u32]], chunk_len: usize) -> Vec<u8> {
+        let mut writer = StreamVByteWriter::with_chunk_len(Vec::new(), chunk_len);
+        for batch in batches {
+            writer.write_values(batch).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    fn read_all(file: &[u8], max_chunk_len: usize) -> std::io::Result<Vec<u32>> {
+        let mut reader = StreamVByteReader::with_max_chunk_len(file, max_chunk_len);
+        let mut batch = Vec::new();
+        let mut result = Vec::new();
+        while reader.read_batch(&mut batch)? {
+            assert!(batch.len() <= max_chunk_len);
+            result.extend_from_slice(&batch);
+        }
+        Ok(result)
+    }
+
+    #[test]
+    fn roundtrip() {
+        let values: Vec<u32> = (0..10_000).map(|x| x * x * 7).collect();
+        for chunk_len in [1, 5, 100, 4096, 20_000] {
+            let file = write_all(&[&values[..3], &[], &values[3..]], chunk_len);
+            assert_eq!(read_all(&file, chunk_len).unwrap(), values);
+        }
+        assert!(write_all(&[], 10).is_empty());
+        assert_eq!(read_all(&[], 10).unwrap(), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn drop_flushes() {
+        let mut file = Vec::new();
+        {
+            let mut writer = StreamVByteWriter::new(&mut file);
+            writer.write_values(&[1, 2, 3]).unwrap();
+        }
+        assert_eq!(read_all(&file, 10).unwrap(), vec![1, 2, 3]);
+    }
+
+    // Accepts `limit` bytes, then fails every write.
+    struct FailingWriter {
+        written: Vec<u8>,
+        limit: usize,
+    }
+
+    impl Write for FailingWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(self.limit - self.written.len());
+            if n == 0 && !buf.is_empty() {
+                return Err(ErrorKind::WriteZero.into());
+            }
+            self.written.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn failed_write_poisons() {
+        let mut sink = FailingWriter {
+            written: Vec::new(),
+            limit: 10,
+        };
+        {
+            let mut writer = StreamVByteWriter::with_chunk_len(&mut sink, 4);
+            assert!(writer.write_values(&[1, 2, 3, 4, 5]).is_err());
+            writer.inner.get_mut().limit = usize::MAX;
+            assert!(writer.write_values(&[6]).is_err());
+            assert!(writer.flush().is_err());
+        }
+        // Neither the retries nor the drop wrote the chunk a second time.
+        assert_eq!(sink.written.len(), 10);
+    }
+
+    #[test]
+    fn bad_input() {
+        let values: Vec<u32> = (0..100).map(|x| x * 1000).collect();
+        let file = write_all(&[&values], 64);
+        for cut in [1, 7, 8, 9, file.len() - 1] {
+            let err = read_all(&file[..cut], 64).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        }
+        assert_eq!(
+            read_all(&file, 63).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        let mut corrupt = file.clone();
+        // Claim one data byte less than the control bytes need.
+        corrupt[4] -= 1;
+        assert_eq!(
+            read_all(&corrupt, 64).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+}
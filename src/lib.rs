@@ -1,37 +1,72 @@
-// SIMD on AArch64 requires nightly as of Rust 1.58
-#![cfg_attr(feature = "aarch64-simd", feature(stdsimd))]
-#![cfg_attr(feature = "aarch64-simd", feature(aarch64_target_feature))]
-// #![feature(stdsimd)]
-// #![feature(aarch64_target_feature)]
+#![cfg_attr(feature = "safe-only", forbid(unsafe_code))]
 
+pub mod adaptive;
+pub mod backend;
+#[cfg(feature = "bytes")]
+pub mod buf;
 pub(crate) mod common;
+pub mod compressed;
+pub mod context;
+pub mod delta;
+pub mod group_varint;
+pub mod interleaved;
+pub mod io;
+pub mod options;
+#[cfg(feature = "rayon")]
+pub mod par;
 pub mod scalar;
+#[cfg(feature = "serde")]
+pub mod serde;
+pub mod splice;
+pub mod stats;
 pub(crate) mod tables;
+pub mod varint;
+pub mod zero124;
 
+// Compiled even without SSSE3 at compile time, so that [Backend::Ssse3] can be
+// selected at runtime.
 #[cfg(all(
     any(target_arch = "x86", target_arch = "x86_64"),
-    target_feature = "ssse3"
+    target_feature = "sse2",
+    not(feature = "safe-only")
 ))]
 pub(crate) mod x86_64;
 
-#[cfg(all(target_arch = "aarch64", feature = "aarch64-simd"))]
-pub mod aarch64;
+#[cfg(all(
+    target_arch = "aarch64",
+    feature = "aarch64-simd",
+    not(feature = "safe-only")
+))]
+pub(crate) mod aarch64;
 
 #[cfg(any(
-    all(target_arch = "aarch64", feature = "aarch64-simd"),
+    all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ),
     all(
         any(target_arch = "x86", target_arch = "x86_64"),
-        target_feature = "ssse3"
+        target_feature = "ssse3",
+        not(feature = "safe-only")
     )
 ))]
 pub mod simd;
 
-#[cfg(test)]
 pub mod safe;
 
+#[cfg(test)]
+mod conformance;
+
+pub use crate::backend::Backend;
 pub use crate::common::{
-    control_bytes_len, exact_compressed_len, max_compressed_len, StreamVbyteError,
+    checked_max_compressed_len, control_bytes_len, exact_compressed_len,
+    exact_compressed_len_delta, exact_compressed_len_for, exact_compressed_len_zigzag,
+    max_compressed_len, StreamVbyteError,
 };
+pub use crate::compressed::{CompressedSlice, CompressedVec};
+pub use crate::context::{Decoder, Encoder, Transform};
+pub use crate::options::DecodeOptions;
 //pub use crate::common::control_bytes_len
 
 /// Encode a slice of `u32` values into a single byte vector.
@@ -62,27 +97,199 @@ pub use crate::common::{
 pub fn encode(values: &[u32]) -> (usize, Vec<u8>) {
     #[cfg(all(
         any(target_arch = "x86", target_arch = "x86_64"),
-        target_feature = "ssse3"
+        target_feature = "ssse3",
+        not(feature = "safe-only")
     ))]
     {
         // println!("Using x86-64 simd");
         return crate::x86_64::encode::encode_simd::<crate::x86_64::encode::NoEncode>(values);
     }
 
-    #[cfg(all(target_arch = "aarch64", feature = "aarch64-simd"))]
+    #[cfg(all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ))]
     {
         // println!("Using aarch64 simd");
-        return crate::aarch64::encode::encode_simd(values);
+        return crate::aarch64::encode::encode_simd::<crate::aarch64::encode::NoEncode>(values);
     }
     #[cfg(not(any(
-        all(target_arch = "aarch64", feature = "aarch64-simd"),
+        all(
+            target_arch = "aarch64",
+            feature = "aarch64-simd",
+            not(feature = "safe-only")
+        ),
         all(
             any(target_arch = "x86", target_arch = "x86_64"),
-            target_feature = "ssse3"
+            target_feature = "ssse3",
+            not(feature = "safe-only")
         )
     )))]
     {
-        crate::scalar::encode::encode(values)
+        crate::scalar::encode(values)
+    }
+}
+
+/// Like [encode], but returns [StreamVbyteError::AllocationFailed] instead of
+/// aborting if the output buffer cannot be allocated.
+///
+/// ```
+/// let (len, bytes) = streamvb::try_encode(&[1, 300, 70000]).unwrap();
+/// assert_eq!((len, bytes), streamvb::encode(&[1, 300, 70000]));
+/// ```
+pub fn try_encode(values: &[u32]) -> Result<(usize, Vec<u8>), StreamVbyteError> {
+    let max_len =
+        checked_max_compressed_len(values.len()).ok_or(StreamVbyteError::AllocationFailed)?;
+    let mut output = Vec::new();
+    output
+        .try_reserve(max_len)
+        .map_err(|_| StreamVbyteError::AllocationFailed)?;
+    encode_into(values, &mut output);
+    Ok((values.len(), output))
+}
+
+/// Encode the longest prefix of `values` that fits into `output`, e.g. a
+/// fixed-size storage page.
+///
+/// Returns the number of values encoded and the number of bytes written. The
+/// written bytes are exactly what [encode] returns for that prefix, so they
+/// can be decoded with [decode].
+///
+/// ```
+/// let values: Vec<u32> = (0..1000).collect();
+/// let mut page = [0u8; 64];
+/// let (count, written) = streamvb::encode_fill(&values, &mut page);
+/// assert_eq!(streamvb::decode(count, &page[..written]).unwrap(), &values[..count]);
+/// assert!(streamvb::encode(&values[..count + 1]).1.len() > page.len());
+/// ```
+pub fn encode_fill(values: &[u32], output: &mut [u8]) -> (usize, usize) {
+    let capacity = output.len();
+    // Every value needs at least one data byte and a quarter control byte.
+    let mut count = values.len().min(capacity / 5 * 4 + 4);
+    let mut data_len = 0;
+    if max_compressed_len(count) > capacity {
+        // Add whole blocks while they fit, then single values.
+        const BLOCK: usize = 64;
+        count = 0;
+        while count + BLOCK <= values.len() {
+            let block_len = exact_compressed_len(&values[count..count + BLOCK]);
+            if control_bytes_len(count + BLOCK) + data_len + block_len > capacity {
+                break;
+            }
+            count += BLOCK;
+            data_len += block_len;
+        }
+        while count < values.len() {
+            let value_len = exact_compressed_len(&values[count..count + 1]);
+            if control_bytes_len(count + 1) + data_len + value_len > capacity {
+                break;
+            }
+            count += 1;
+            data_len += value_len;
+        }
+    } else {
+        data_len = exact_compressed_len(&values[..count]);
+    }
+
+    let num_controls = control_bytes_len(count);
+    let (control, data) = output[..num_controls + data_len].split_at_mut(num_controls);
+    encode_to_slices(&values[..count], control, data);
+    (count, num_controls + data_len)
+}
+
+/// Encode `values` into separate control and data streams. `control` and
+/// `data` must have exactly the lengths given by [control_bytes_len] and
+/// [exact_compressed_len].
+#[cfg(not(feature = "safe-only"))]
+pub(crate) fn encode_to_slices(values: &[u32], control: &mut [u8], data: &mut [u8]) {
+    // The fast encoders may write up to 12 bytes past the end of their data,
+    // so the last 16 or more values, which use at least one byte each, are
+    // encoded separately. Their data bytes absorb the overrun.
+    let head = values.len().saturating_sub(16) & !7;
+    let written = if head > 0 {
+        // Safety: `control` holds `head / 4` bytes, and `data` holds the data
+        // bytes of `head` values plus at least 16 more.
+        unsafe {
+            let end = encode_worker(&values[..head], control.as_mut_ptr(), data.as_mut_ptr());
+            end.offset_from(data.as_ptr()) as usize
+        }
+    } else {
+        0
+    };
+    let (_, tail) = encode(&values[head..]);
+    let (tail_control, tail_data) = tail.split_at(control_bytes_len(values.len() - head));
+    control[head / 4..].copy_from_slice(tail_control);
+    data[written..].copy_from_slice(tail_data);
+}
+
+#[cfg(feature = "safe-only")]
+pub(crate) use crate::safe::encode_to_slices;
+
+/// Encode `values` and append the bytes to `output`, using SIMD if available.
+#[cfg(not(feature = "safe-only"))]
+pub(crate) fn encode_into(values: &[u32], output: &mut Vec<u8>) {
+    if values.is_empty() {
+        return;
+    }
+    output.reserve(max_compressed_len(values.len()));
+    // Safety: `output` has room for the worst case, which bounds everything
+    // `encode_worker` writes.
+    unsafe {
+        let control = output.as_mut_ptr().add(output.len());
+        let data = control.add(control_bytes_len(values.len()));
+        let end = encode_worker(values, control, data);
+        let len = end.offset_from(output.as_ptr()) as usize;
+        output.set_len(len);
+    }
+}
+
+#[cfg(feature = "safe-only")]
+pub(crate) use crate::safe::encode_into;
+
+// Writes the control and data bytes of `values` and returns the end of the
+// data, using SIMD if available. May write past the end of the data, but stays
+// within `max_compressed_len(values.len())` bytes from `control`.
+#[cfg(not(feature = "safe-only"))]
+#[allow(clippy::needless_return)]
+pub(crate) unsafe fn encode_worker(values: &[u32], control: *mut u8, data: *mut u8) -> *mut u8 {
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    ))]
+    {
+        return crate::x86_64::encode::encode_worker::<crate::x86_64::encode::NoEncode>(
+            values.len(),
+            values.as_ptr(),
+            control,
+            data,
+        );
+    }
+
+    #[cfg(all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ))]
+    {
+        return crate::aarch64::encode::encode_worker::<crate::aarch64::encode::NoEncode>(
+            values.len(),
+            values.as_ptr(),
+            control,
+            data,
+        );
+    }
+
+    #[cfg(not(any(
+        all(
+            any(target_arch = "x86", target_arch = "x86_64"),
+            target_feature = "ssse3"
+        ),
+        all(target_arch = "aarch64", feature = "aarch64-simd"),
+    )))]
+    {
+        crate::scalar::encode::encode_worker(values.len(), values.as_ptr(), control, data)
     }
 }
 
@@ -110,28 +317,173 @@ pub fn encode(values: &[u32]) -> (usize, Vec<u8>) {
 pub fn decode(len: usize, input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
     #[cfg(all(
         any(target_arch = "x86", target_arch = "x86_64"),
-        target_feature = "ssse3"
+        target_feature = "ssse3",
+        not(feature = "safe-only")
     ))]
     {
         // println!("Using x86-64 simd");
         return crate::x86_64::decode::decode_simd::<crate::x86_64::decode::NoDecode>(len, input);
     }
 
-    #[cfg(all(target_arch = "aarch64", feature = "aarch64-simd"))]
+    #[cfg(all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ))]
     {
         // println!("Using aarch64 simd");
-        return crate::aarch64::decode::decode_simd(len, input);
+        return crate::aarch64::decode::decode_simd::<crate::aarch64::decode::NoDecode>(len, input);
     }
 
     #[cfg(not(any(
-        all(target_arch = "aarch64", feature = "aarch64-simd"),
+        all(
+            target_arch = "aarch64",
+            feature = "aarch64-simd",
+            not(feature = "safe-only")
+        ),
         all(
             any(target_arch = "x86", target_arch = "x86_64"),
-            target_feature = "ssse3"
+            target_feature = "ssse3",
+            not(feature = "safe-only")
         )
     )))]
     {
         // println!("Using scalar");
-        crate::scalar::decode::decode(len, input)
+        crate::scalar::decode(len, input)
+    }
+}
+
+/// Like [decode], but returns [StreamVbyteError::AllocationFailed] instead of
+/// aborting if the output vector cannot be allocated.
+///
+/// As with [decode], `len` is checked against the size of `input` before
+/// anything is allocated, so a bogus `len` is rejected cheaply.
+///
+/// ```
+/// let (len, bytes) = streamvb::encode(&[1, 300, 70000]);
+/// assert_eq!(streamvb::try_decode(len, &bytes).unwrap(), vec![1, 300, 70000]);
+/// assert!(streamvb::try_decode(usize::MAX, &bytes).is_err());
+/// ```
+pub fn try_decode(len: usize, input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
+    let mut output = Vec::new();
+    if len == 0 {
+        return Ok(output);
+    }
+    let num_controls = control_bytes_len(len);
+    if num_controls >= input.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    output
+        .try_reserve(len)
+        .map_err(|_| StreamVbyteError::AllocationFailed)?;
+    let (control, data) = input.split_at(num_controls);
+    decode_parts_into(len, control, data, &mut output)?;
+    Ok(output)
+}
+
+/// Like [decode], but applies the limits and checks in `options`. Use this for
+/// untrusted input.
+///
+/// ```
+/// use streamvb::DecodeOptions;
+/// let (len, bytes) = streamvb::encode(&[1, 300, 70000]);
+/// let options = DecodeOptions::new().max_len(2);
+/// assert!(streamvb::decode_with_options(len, &bytes, &options).is_err());
+/// ```
+pub fn decode_with_options(
+    len: usize,
+    input: &[u8],
+    options: &DecodeOptions,
+) -> Result<Vec<u32>, StreamVbyteError> {
+    let mut output = Vec::new();
+    options.decode_parts_into(len, input, &mut output, decode_parts_into)?;
+    Ok(output)
+}
+
+/// Decode `len` values from separate control and data streams and append them
+/// to `output`, using SIMD if available. `data` may extend past the bytes used
+/// by the `len` values.
+#[allow(clippy::needless_return)]
+pub(crate) fn decode_parts_into(
+    len: usize,
+    control: &[u8],
+    data: &[u8],
+    output: &mut Vec<u32>,
+) -> Result<(), StreamVbyteError> {
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    ))]
+    {
+        return crate::x86_64::decode::decode_parts_into_simd::<crate::x86_64::decode::NoDecode>(
+            len, control, data, output,
+        );
+    }
+
+    #[cfg(all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ))]
+    {
+        return crate::aarch64::decode::decode_parts_into_simd::<crate::aarch64::decode::NoDecode>(
+            len, control, data, output,
+        );
+    }
+
+    #[cfg(not(any(
+        all(
+            any(target_arch = "x86", target_arch = "x86_64"),
+            target_feature = "ssse3"
+        ),
+        all(target_arch = "aarch64", feature = "aarch64-simd"),
+        feature = "safe-only"
+    )))]
+    {
+        crate::scalar::decode::decode_parts_into(len, control, data, output)
+    }
+
+    #[cfg(feature = "safe-only")]
+    {
+        crate::safe::decode_parts_into(len, control, data, output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode, encode_fill, try_decode, try_encode};
+    use crate::{checked_max_compressed_len, max_compressed_len, StreamVbyteError};
+
+    #[test]
+    fn encode_fill_prefix() {
+        let values = crate::common::sample_values(5000);
+        for capacity in [0, 1, 2, 5, 6, 17, 100, 4096, 16384, 50000] {
+            let mut page = vec![0xaa; capacity];
+            let (count, written) = encode_fill(&values, &mut page);
+            let (_, want) = encode(&values[..count]);
+            assert_eq!(&page[..written], &want[..], "capacity {}", capacity);
+            assert!(page[written..].iter().all(|&b| b == 0xaa));
+            if count < values.len() {
+                assert!(encode(&values[..count + 1]).1.len() > capacity);
+            }
+        }
+    }
+
+    #[test]
+    fn checked_sizes() {
+        assert_eq!(checked_max_compressed_len(5), Some(22));
+        assert_eq!(checked_max_compressed_len(usize::MAX / 4 + 1), None);
+        assert_eq!(max_compressed_len(usize::MAX), usize::MAX);
+        assert_eq!(crate::control_bytes_len(usize::MAX), usize::MAX / 4 + 1);
+
+        let values: Vec<u32> = (0..1000).map(|x| x * 1001).collect();
+        let (len, bytes) = try_encode(&values).unwrap();
+        assert_eq!((len, &bytes), (values.len(), &encode(&values).1));
+        assert_eq!(try_decode(len, &bytes).unwrap(), values);
+        assert!(matches!(
+            try_decode(usize::MAX, &bytes),
+            Err(StreamVbyteError::DecodeOutOfBounds)
+        ));
     }
 }
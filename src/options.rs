@@ -0,0 +1,242 @@
+//! Configurable limits for decoding untrusted input.
+use crate::common::{control_bytes_len, data_bytes_len, StreamVbyteError};
+
+/// Limits and checks applied by [crate::decode_with_options] and the
+/// `decode_with_options` functions of the other modules, including the zigzag
+/// and delta decoders.
+///
+/// Independent of the options, `len` is checked against `input.len()` before
+/// anything is allocated: every value needs at least one data byte plus a
+/// quarter control byte.
+///
+/// ```
+/// use streamvb::{DecodeOptions, StreamVbyteError};
+/// let options = DecodeOptions::new().max_len(1000).reject_trailing_bytes(true);
+/// let (len, mut bytes) = streamvb::encode(&[1, 300, 70000]);
+/// assert_eq!(streamvb::decode_with_options(len, &bytes, &options).unwrap(), vec![1, 300, 70000]);
+/// bytes.push(0);
+/// assert!(matches!(
+///     streamvb::decode_with_options(len, &bytes, &options),
+///     Err(StreamVbyteError::TrailingBytes)
+/// ));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeOptions {
+    max_len: usize,
+    max_alloc: usize,
+    reject_trailing_bytes: bool,
+    require_canonical: bool,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        DecodeOptions {
+            max_len: usize::MAX,
+            max_alloc: usize::MAX,
+            reject_trailing_bytes: false,
+            require_canonical: false,
+        }
+    }
+}
+
+impl DecodeOptions {
+    /// Options without any limits, matching the behavior of [crate::decode]
+    /// except for the upfront `len` check.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject inputs with more than `max_len` values.
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Reject inputs whose decoded values would take up more than `max_alloc`
+    /// bytes.
+    pub fn max_alloc(mut self, max_alloc: usize) -> Self {
+        self.max_alloc = max_alloc;
+        self
+    }
+
+    /// Reject inputs with bytes after the end of the encoded values.
+    pub fn reject_trailing_bytes(mut self, reject: bool) -> Self {
+        self.reject_trailing_bytes = reject;
+        self
+    }
+
+    /// Reject encodings that [crate::encode] would not produce, i.e., values
+    /// stored in more bytes than needed or set bits in the unused part of the
+    /// last control byte.
+    pub fn require_canonical(mut self, require: bool) -> Self {
+        self.require_canonical = require;
+        self
+    }
+
+    /// Shorthand for [crate::decode_with_options] with these options.
+    pub fn decode(&self, len: usize, input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
+        crate::decode_with_options(len, input, self)
+    }
+
+    /// Check `len` and `input` against the options and return the control
+    /// bytes and the data bytes used by the `len` values.
+    pub(crate) fn validate<'a>(
+        &self,
+        len: usize,
+        input: &'a [u8],
+    ) -> Result<(&'a [u8], &'a [u8]), StreamVbyteError> {
+        let num_controls = control_bytes_len(len);
+        // Every value takes at least one data byte.
+        if input.len() < num_controls || input.len() - num_controls < len {
+            return Err(StreamVbyteError::DecodeOutOfBounds);
+        }
+        if len > self.max_len || len > self.max_alloc / std::mem::size_of::<u32>() {
+            return Err(StreamVbyteError::LimitExceeded);
+        }
+        let (control, data) = input.split_at(num_controls);
+        let data_len = data_bytes_len(control, len);
+        if data_len > data.len() {
+            return Err(StreamVbyteError::DecodeOutOfBounds);
+        }
+        if self.reject_trailing_bytes && data_len < data.len() {
+            return Err(StreamVbyteError::TrailingBytes);
+        }
+        let data = &data[..data_len];
+        if self.require_canonical && !is_canonical(len, control, data) {
+            return Err(StreamVbyteError::NonCanonical);
+        }
+        Ok((control, data))
+    }
+
+    /// Decode with `decode_parts`, one of the backends' split-stream decoders,
+    /// and apply all checks.
+    pub(crate) fn decode_parts_into<F>(
+        &self,
+        len: usize,
+        input: &[u8],
+        output: &mut Vec<u32>,
+        decode_parts: F,
+    ) -> Result<(), StreamVbyteError>
+    where
+        F: FnOnce(usize, &[u8], &[u8], &mut Vec<u32>) -> Result<(), StreamVbyteError>,
+    {
+        let (control, data) = self.validate(len, input)?;
+        output
+            .try_reserve(len)
+            .map_err(|_| StreamVbyteError::AllocationFailed)?;
+        decode_parts(len, control, data, output)
+    }
+}
+
+// Checks the encoded bytes rather than the decoded values, so that it works
+// the same for every transformation of the values.
+fn is_canonical(len: usize, control: &[u8], data: &[u8]) -> bool {
+    if !len.is_multiple_of(4) && control[control.len() - 1] >> (2 * (len % 4)) != 0 {
+        return false;
+    }
+    let mut offset = 0;
+    (0..len).all(|i| {
+        let code = (control[i / 4] >> (2 * (i % 4))) & 0x3;
+        offset += code as usize + 1;
+        // A value stored in more than one byte must need its highest byte.
+        code == 0 || data[offset - 1] != 0
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DecodeOptions;
+    use crate::StreamVbyteError;
+
+    #[test]
+    fn limits() {
+        let values: Vec<u32> = (0..100).map(|x| x * 999).collect();
+        let (len, bytes) = crate::encode(&values);
+        let decode = |options: DecodeOptions, len, input: &[u8]| options.decode(len, input);
+
+        assert_eq!(decode(DecodeOptions::new(), len, &bytes).unwrap(), values);
+        assert!(matches!(
+            decode(DecodeOptions::new().max_len(99), len, &bytes),
+            Err(StreamVbyteError::LimitExceeded)
+        ));
+        assert!(matches!(
+            decode(DecodeOptions::new().max_alloc(399), len, &bytes),
+            Err(StreamVbyteError::LimitExceeded)
+        ));
+        assert!(decode(DecodeOptions::new().max_alloc(400), len, &bytes).is_ok());
+        // Rejected before looking at the control bytes.
+        for bogus in [bytes.len(), usize::MAX / 2, usize::MAX] {
+            assert!(matches!(
+                decode(DecodeOptions::new(), bogus, &bytes),
+                Err(StreamVbyteError::DecodeOutOfBounds)
+            ));
+        }
+    }
+
+    #[test]
+    fn trailing_and_canonical() {
+        let strict = DecodeOptions::new()
+            .reject_trailing_bytes(true)
+            .require_canonical(true);
+        let (len, bytes) = crate::encode(&[1, 300, 70000, 0xdeadbeef, 7]);
+        assert!(strict.decode(len, &bytes).is_ok());
+        assert!(crate::scalar::decode_with_options(len, &bytes, &strict).is_ok());
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(DecodeOptions::new().decode(len, &trailing).is_ok());
+        assert!(matches!(
+            strict.decode(len, &trailing),
+            Err(StreamVbyteError::TrailingBytes)
+        ));
+
+        // 7 stored in two bytes.
+        let mut wide = bytes.clone();
+        wide[1] |= 0b01;
+        wide.push(0);
+        assert_eq!(DecodeOptions::new().decode(len, &wide).unwrap()[4], 7);
+        assert!(matches!(
+            strict.decode(len, &wide),
+            Err(StreamVbyteError::NonCanonical)
+        ));
+        assert!(matches!(
+            crate::scalar::decode_with_options(len, &wide, &strict),
+            Err(StreamVbyteError::NonCanonical)
+        ));
+
+        // Garbage in the unused bits of the last control byte.
+        let mut dirty = bytes;
+        dirty[1] |= 0b1100;
+        assert!(matches!(
+            strict.decode(len, &dirty),
+            Err(StreamVbyteError::NonCanonical)
+        ));
+    }
+
+    #[cfg(any(
+        all(
+            any(target_arch = "x86", target_arch = "x86_64"),
+            target_feature = "ssse3",
+            not(feature = "safe-only")
+        ),
+        all(
+            target_arch = "aarch64",
+            feature = "aarch64-simd",
+            not(feature = "safe-only")
+        )
+    ))]
+    #[test]
+    fn zigzag() {
+        let values = [0, 1, u32::MAX, 300, 0x8000_0000];
+        let mut bytes = Vec::new();
+        let len = crate::simd::zigzag_encode_into(&values, &mut bytes);
+        let strict = DecodeOptions::new().reject_trailing_bytes(true);
+        let decode = crate::simd::zigzag_decode_with_options;
+        assert_eq!(decode(len, &bytes, &strict).unwrap(), values);
+        bytes.push(0);
+        assert!(matches!(
+            decode(len, &bytes, &strict),
+            Err(StreamVbyteError::TrailingBytes)
+        ));
+    }
+}
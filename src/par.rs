@@ -0,0 +1,173 @@
+//! Parallel encoding and decoding of large inputs using rayon.
+//!
+//! The output of [encode] is byte-identical to [crate::encode], and [decode]
+//! accepts anything produced by either. The input is split into chunks of
+//! [CHUNK_LEN] values. Since that is a multiple of 4, every chunk owns whole
+//! control bytes, and each chunk's data bytes can be located up front.
+use rayon::prelude::*;
+
+use crate::common::{control_bytes_len, data_bytes_len, exact_compressed_len, StreamVbyteError};
+
+/// Number of values handled by one task.
+pub const CHUNK_LEN: usize = 1 << 16;
+
+/// Encode `values` in parallel. Produces the same bytes as [crate::encode].
+///
+/// ```
+/// let values: Vec<u32> = (0..200_000).collect();
+/// let (len, bytes) = streamvb::par::encode(&values);
+/// assert_eq!((len, bytes.clone()), streamvb::encode(&values));
+/// assert_eq!(streamvb::par::decode(len, &bytes).unwrap(), values);
+/// ```
+pub fn encode(values: &[u32]) -> (usize, Vec<u8>) {
+    let items = values.len();
+    if items <= CHUNK_LEN {
+        return crate::encode(values);
+    }
+    let data_lens: Vec<usize> = values
+        .par_chunks(CHUNK_LEN)
+        .map(exact_compressed_len)
+        .collect();
+    let num_controls = control_bytes_len(items);
+    let mut output = vec![0u8; num_controls + data_lens.iter().sum::<usize>()];
+
+    let (control, mut data) = output.split_at_mut(num_controls);
+    let mut data_slots = Vec::with_capacity(data_lens.len());
+    for &data_len in &data_lens {
+        let (slot, rest) = data.split_at_mut(data_len);
+        data_slots.push(slot);
+        data = rest;
+    }
+    values
+        .par_chunks(CHUNK_LEN)
+        .zip(control.par_chunks_mut(CHUNK_LEN / 4))
+        .zip(data_slots.into_par_iter())
+        .for_each(|((chunk, control), data)| crate::encode_to_slices(chunk, control, data));
+    (items, output)
+}
+
+/// Decode `len` values in parallel. Returns an error under the same
+/// conditions as [crate::decode].
+pub fn decode(len: usize, input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
+    if len <= CHUNK_LEN {
+        return crate::decode(len, input);
+    }
+    let num_controls = control_bytes_len(len);
+    if num_controls >= input.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    let (control, mut data) = input.split_at(num_controls);
+
+    // Each chunk's data length follows from its control bytes alone, so the
+    // prefix sums give every chunk's starting offset.
+    let data_lens: Vec<usize> = control
+        .par_chunks(CHUNK_LEN / 4)
+        .enumerate()
+        .map(|(i, control)| data_bytes_len(control, (len - i * CHUNK_LEN).min(CHUNK_LEN)))
+        .collect();
+    if data_lens.iter().sum::<usize>() > data.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    let mut data_slots = Vec::with_capacity(data_lens.len());
+    for &data_len in &data_lens {
+        let (slot, rest) = data.split_at(data_len);
+        data_slots.push(slot);
+        data = rest;
+    }
+
+    let mut output = vec![0u32; len];
+    output
+        .par_chunks_mut(CHUNK_LEN)
+        .zip(control.par_chunks(CHUNK_LEN / 4))
+        .zip(data_slots.into_par_iter())
+        .try_for_each(|((output, control), data)| decode_parts_to_slice(control, data, output))?;
+    Ok(output)
+}
+
+#[cfg(feature = "safe-only")]
+use crate::safe::decode_parts_to_slice;
+
+#[cfg(not(feature = "safe-only"))]
+#[allow(clippy::needless_return)]
+fn decode_parts_to_slice(
+    control: &[u8],
+    data: &[u8],
+    output: &mut [u32],
+) -> Result<(), StreamVbyteError> {
+    // Safety: `output` is valid for writing `output.len()` values.
+    unsafe {
+        #[cfg(all(
+            any(target_arch = "x86", target_arch = "x86_64"),
+            target_feature = "ssse3",
+            not(feature = "safe-only")
+        ))]
+        {
+            return crate::x86_64::decode::decode_parts_to_ptr_simd::<
+                crate::x86_64::decode::NoDecode,
+            >(output.len(), control, data, output.as_mut_ptr());
+        }
+
+        #[cfg(all(target_arch = "aarch64", feature = "aarch64-simd"))]
+        {
+            return crate::aarch64::decode::decode_parts_to_ptr_simd::<
+                crate::aarch64::decode::NoDecode,
+            >(output.len(), control, data, output.as_mut_ptr());
+        }
+
+        #[cfg(not(any(
+            all(
+                any(target_arch = "x86", target_arch = "x86_64"),
+                target_feature = "ssse3"
+            ),
+            all(target_arch = "aarch64", feature = "aarch64-simd")
+        )))]
+        {
+            crate::scalar::decode::decode_parts_to_ptr(
+                output.len(),
+                control,
+                data,
+                output.as_mut_ptr(),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::{decode, encode, CHUNK_LEN};
+
+    #[test]
+    fn matches_serial() {
+        let mut rng = rand::thread_rng();
+        for len in [
+            0,
+            5,
+            CHUNK_LEN,
+            CHUNK_LEN + 1,
+            3 * CHUNK_LEN + 17,
+            5 * CHUNK_LEN,
+        ] {
+            let values: Vec<u32> = (0..len)
+                .map(|_| rng.gen::<u32>() >> rng.gen_range(0..32))
+                .collect();
+            let (n, bytes) = encode(&values);
+            assert_eq!((n, &bytes), (len, &crate::encode(&values).1));
+            assert_eq!(decode(n, &bytes).unwrap(), values);
+        }
+        // All 1-byte values, so the tail of each chunk is as short as it gets.
+        let small = vec![7u32; 2 * CHUNK_LEN + 3];
+        let (n, bytes) = encode(&small);
+        assert_eq!(bytes, crate::encode(&small).1);
+        assert_eq!(decode(n, &bytes).unwrap(), small);
+    }
+
+    #[test]
+    fn truncated() {
+        let values: Vec<u32> = (0..3 * CHUNK_LEN as u32).collect();
+        let (len, bytes) = encode(&values);
+        assert!(decode(len, &bytes[..bytes.len() - 1]).is_err());
+        assert!(decode(len + 4 * CHUNK_LEN, &bytes).is_err());
+    }
+}
@@ -1,93 +1,133 @@
-use std::convert::TryInto;
-
-use crate::common::{control_bytes_len, max_compressed_len};
+//! Reference implementation in safe Rust.
+//!
+//! Slower than the other backends, but produces the same bytes and never
+//! panics on malformed input. With the `safe-only` feature, every entry point
+//! of the crate uses this module and the crate contains no `unsafe` code.
+#![forbid(unsafe_code)]
+use crate::common::{control_bytes_len, data_bytes_len, encode_one, StreamVbyteError};
+use crate::options::DecodeOptions;
 
+/// Encode `input` like [crate::encode].
+///
+/// ```
+/// let values = [1, 300, 70000, 0xdeadbeef];
+/// assert_eq!(streamvb::safe::encode(&values), streamvb::encode(&values));
+/// ```
 pub fn encode(input: &[u32]) -> (usize, Vec<u8>) {
-    let items = input.len();
-    if items == 0 {
-        return (0, Vec::new());
-    }
-
-    let mut output: Vec<u8> = Vec::with_capacity(max_compressed_len(items));
-    output.resize(control_bytes_len(items), 0);
-    let mut controls: Vec<u8> = Vec::with_capacity(control_bytes_len(items));
+    let mut output = Vec::new();
+    encode_into(input, &mut output);
+    (input.len(), output)
+}
 
-    let mut shift: u8 = 0;
-    let mut key: u8 = 0;
-    for val in input {
-        if shift == 8 {
-            shift = 0;
-            controls.push(key);
-            key = 0;
-        }
-        let code = encode_single(*val, &mut output);
-        key |= code << shift;
-        shift += 2;
+/// Encode `values` and append the bytes to `output`.
+pub(crate) fn encode_into(values: &[u32], output: &mut Vec<u8>) {
+    let start = output.len();
+    let num_controls = control_bytes_len(values.len());
+    output.resize(start + num_controls, 0);
+    for (i, &value) in values.iter().enumerate() {
+        let code = encode_one(value);
+        output[start + i / 4] |= (code << (2 * (i % 4))) as u8;
+        output.extend_from_slice(&value.to_le_bytes()[..code as usize + 1]);
     }
-    controls.push(key);
-    debug_assert_eq!(controls.len(), control_bytes_len(items));
-    let (control, _) = output.split_at_mut(control_bytes_len(items));
-    control.copy_from_slice(&controls);
-    (items, output)
 }
 
-fn encode_single(val: u32, out: &mut Vec<u8>) -> u8 {
-    let bytes: [u8; 4] = val.to_le_bytes();
-    if val < (1 << 8) {
-        out.push(bytes[0]);
-        0
-    } else if val < (1 << 16) {
-        out.extend_from_slice(&bytes[0..2]);
-        1
-    } else if val < (1 << 24) {
-        out.extend_from_slice(&bytes[0..3]);
-        2
-    } else {
-        out.extend_from_slice(&bytes);
-        3
+/// Encode `values` into separate control and data streams of exactly the
+/// right lengths.
+#[cfg_attr(not(feature = "safe-only"), allow(dead_code))]
+pub(crate) fn encode_to_slices(values: &[u32], control: &mut [u8], data: &mut [u8]) {
+    control.fill(0);
+    let mut offset = 0;
+    for (i, &value) in values.iter().enumerate() {
+        let code = encode_one(value);
+        control[i / 4] |= (code << (2 * (i % 4))) as u8;
+        let width = code as usize + 1;
+        data[offset..offset + width].copy_from_slice(&value.to_le_bytes()[..width]);
+        offset += width;
     }
 }
 
-pub fn decode(len: usize, input: &[u8]) -> Vec<u32> {
+/// Decode `len` values like [crate::decode].
+///
+/// ```
+/// let (len, bytes) = streamvb::encode(&[1, 300, 70000]);
+/// assert_eq!(streamvb::safe::decode(len, &bytes).unwrap(), vec![1, 300, 70000]);
+/// assert!(streamvb::safe::decode(len, &bytes[..4]).is_err());
+/// ```
+pub fn decode(len: usize, input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
     if len == 0 {
-        return Vec::new();
+        return Ok(Vec::new());
     }
-    let num_control_bytes = control_bytes_len(len);
-    let control = &input[0..num_control_bytes];
-    let data = &input[num_control_bytes..];
-    let mut result = Vec::with_capacity(len);
-    let mut offset = 0;
-    let mut key = control[0];
-    let mut control_offset = 1;
-    let mut shift = 0;
-    for _ in 0..len {
-        if shift == 8 {
-            key = control[control_offset];
-            control_offset += 1;
-            shift = 0;
-        }
-        let (val, bytes) = decode_single(&data[offset..], (key >> shift) & 0x3);
-        offset += bytes;
-        result.push(val);
-        shift += 2;
+    let num_controls = control_bytes_len(len);
+    if num_controls >= input.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
     }
-    result
+    let (control, data) = input.split_at(num_controls);
+    let mut output = Vec::new();
+    decode_parts_into(len, control, data, &mut output)?;
+    Ok(output)
+}
+
+/// Like [decode], but applies the limits and checks in `options`.
+pub fn decode_with_options(
+    len: usize,
+    input: &[u8],
+    options: &DecodeOptions,
+) -> Result<Vec<u32>, StreamVbyteError> {
+    let mut output = Vec::new();
+    options.decode_parts_into(len, input, &mut output, decode_parts_into)?;
+    Ok(output)
+}
+
+/// Decode `len` values from separate control and data streams and append them
+/// to `output`. `data` may extend past the bytes used by the `len` values.
+pub(crate) fn decode_parts_into(
+    len: usize,
+    control: &[u8],
+    data: &[u8],
+    output: &mut Vec<u32>,
+) -> Result<(), StreamVbyteError> {
+    check_parts(len, control, data)?;
+    let start = output.len();
+    output.resize(start + len, 0);
+    decode_to_slice(control, data, &mut output[start..]);
+    Ok(())
 }
 
-pub fn decode_single(data: &[u8], control: u8) -> (u32, usize) {
-    if control == 0 {
-        // 1 byte
-        (data[0] as u32, 1)
-    } else if control == 1 {
-        // 2 bytes
-        (u16::from_le_bytes([data[0], data[1]]) as u32, 2)
-    } else if control == 2 {
-        (u32::from_le_bytes([data[0], data[1], data[2], 0]), 3)
-    } else {
-        (u32::from_le_bytes(data[0..4].try_into().unwrap()), 4)
+/// Decode `output.len()` values from separate control and data streams.
+#[cfg(feature = "rayon")]
+#[cfg_attr(not(feature = "safe-only"), allow(dead_code))]
+pub(crate) fn decode_parts_to_slice(
+    control: &[u8],
+    data: &[u8],
+    output: &mut [u32],
+) -> Result<(), StreamVbyteError> {
+    check_parts(output.len(), control, data)?;
+    decode_to_slice(control, data, output);
+    Ok(())
+}
+
+fn check_parts(len: usize, control: &[u8], data: &[u8]) -> Result<(), StreamVbyteError> {
+    if control.len() < control_bytes_len(len) || data_bytes_len(control, len) > data.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
     }
+    Ok(())
 }
 
+// The caller checks that `control` and `data` are long enough.
+fn decode_to_slice(control: &[u8], data: &[u8], output: &mut [u32]) {
+    let mut offset = 0;
+    for (quad, &key) in output.chunks_mut(4).zip(control) {
+        for (i, value) in quad.iter_mut().enumerate() {
+            let width = ((key >> (2 * i)) & 0x3) as usize + 1;
+            let mut bytes = [0u8; 4];
+            bytes[..width].copy_from_slice(&data[offset..offset + width]);
+            *value = u32::from_le_bytes(bytes);
+            offset += width;
+        }
+    }
+}
+
+#[cfg(test)]
 mod tests {
     use super::{decode, encode};
     #[test]
@@ -126,8 +166,19 @@ mod tests {
         for input in inputs {
             //println!("{:?}", input);
             let (len, bytes) = encode(input);
-            let decoded = decode(len, &bytes);
+            let decoded = decode(len, &bytes).unwrap();
             assert_eq!(input, &decoded);
         }
     }
+
+    #[test]
+    fn truncated() {
+        let values: Vec<u32> = (0..37).map(|x| x * x * x * 1001).collect();
+        let (len, bytes) = encode(&values);
+        for end in 0..bytes.len() {
+            assert!(decode(len, &bytes[..end]).is_err());
+        }
+        assert!(decode(len + 1, &bytes).is_err());
+        assert!(decode(usize::MAX, &bytes).is_err());
+    }
 }
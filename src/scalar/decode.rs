@@ -1,48 +1,87 @@
 use crate::common::{control_bytes_len, StreamVbyteError};
+use crate::options::DecodeOptions;
 
+// The format is little-endian, so the bytes are assembled explicitly instead
+// of being copied into a native `u32`.
 #[inline]
 unsafe fn extract_bytes(data: *const u8, count: u8) -> u32 {
-    if count == 1 {
-        // 1 byte
-        *data as u32
-    } else if count == 2 {
-        let mut result: u32 = 0;
-        std::ptr::copy_nonoverlapping(data, (&mut result) as *mut u32 as *mut u8, 2);
-        // 2 bytes
-        result
-    } else if count == 3 {
-        let mut result: u32 = 0;
-        std::ptr::copy_nonoverlapping(data, (&mut result) as *mut u32 as *mut u8, 3);
-        result
-    } else {
-        let mut result: u32 = 0;
-        std::ptr::copy_nonoverlapping(data, (&mut result) as *mut u32 as *mut u8, 4);
-        result
-    }
+    let mut bytes = [0u8; 4];
+    std::ptr::copy_nonoverlapping(data, bytes.as_mut_ptr(), count as usize);
+    u32::from_le_bytes(bytes)
 }
 
 pub fn decode(len: usize, input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
     if len == 0 {
         return Ok(Vec::new());
     }
-    let end: *const u8 = input.as_ptr_range().end;
     let num_control_bytes = control_bytes_len(len);
     if num_control_bytes >= input.len() {
         return Err(StreamVbyteError::DecodeOutOfBounds);
     }
-    let control: *const u8 = input.as_ptr();
-    let data: *const u8 = unsafe { input.as_ptr().add(num_control_bytes) };
+    let (control, data) = input.split_at(num_control_bytes);
     let mut result: Vec<u32> = Vec::with_capacity(len);
-    let out: *mut u32 = result.as_mut_ptr();
+    decode_parts_into(len, control, data, &mut result)?;
+    Ok(result)
+}
+
+/// Like [decode], but applies the limits and checks in `options`.
+pub fn decode_with_options(
+    len: usize,
+    input: &[u8],
+    options: &DecodeOptions,
+) -> Result<Vec<u32>, StreamVbyteError> {
+    let mut output = Vec::new();
+    options.decode_parts_into(len, input, &mut output, decode_parts_into)?;
+    Ok(output)
+}
+
+/// Decode `len` values from separate control and data streams and append them
+/// to `output`. `data` may extend past the bytes used by the `len` values.
+pub(crate) fn decode_parts_into(
+    len: usize,
+    control: &[u8],
+    data: &[u8],
+    output: &mut Vec<u32>,
+) -> Result<(), StreamVbyteError> {
+    if len == 0 {
+        return Ok(());
+    }
+    // Checked before reserving, so a bogus `len` cannot trigger a huge
+    // allocation.
+    if control.len() < control_bytes_len(len) {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    output.reserve(len);
     unsafe {
-        let (_out, ok) = decode_unroll_inner_checked(control, data, end, out, len, |x| x);
-        if ok {
-            result.set_len(len);
-        } else {
-            return Err(StreamVbyteError::DecodeOutOfBounds);
-        }
+        let out: *mut u32 = output.as_mut_ptr().add(output.len());
+        decode_parts_to_ptr(len, control, data, out)?;
+        output.set_len(output.len() + len);
     }
-    Ok(result)
+    Ok(())
+}
+
+/// Like [decode_parts_into], but writes exactly `len` values to `out`.
+///
+/// Safety: `out` must be valid for writing `len` values.
+pub(crate) unsafe fn decode_parts_to_ptr(
+    len: usize,
+    control: &[u8],
+    data: &[u8],
+    out: *mut u32,
+) -> Result<(), StreamVbyteError> {
+    if len == 0 {
+        return Ok(());
+    }
+    if control.len() < control_bytes_len(len) {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    let end: *const u8 = data.as_ptr_range().end;
+    let (_out, ok) =
+        decode_unroll_inner_checked(control.as_ptr(), data.as_ptr(), end, out, len, |x| x);
+    if !ok {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    Ok(())
 }
 
 // Returns the final output pointer and whether all values were in bounds.
@@ -54,10 +93,10 @@ pub(crate) unsafe fn decode_unroll_inner_checked<F>(
     end: *const u8,
     mut out: *mut u32,
     len: usize,
-    f: F,
+    mut f: F,
 ) -> (*mut u32, bool)
 where
-    F: Fn(u32) -> u32,
+    F: FnMut(u32) -> u32,
 {
     // We know: control < data, Therfore, if we run out of bounds it will be
     // the data pointer.
@@ -65,7 +104,9 @@ where
     while len_remaining >= 4 {
         let key = *control as u32;
 
-        if data.add(16) >= end {
+        // Compare lengths rather than pointers, so no pointer is ever formed
+        // past `end`.
+        if end.offset_from(data) <= 16 {
             break;
         }
         len_remaining -= 4;
@@ -77,24 +118,28 @@ where
         let key3 = (key >> 4) & 0x3;
         let key4 = key >> 6;
 
-        let val: u32 = (data as *const u32).read_unaligned();
+        let val: u32 = u32::from_le((data as *const u32).read_unaligned());
         *out = f(val & !((!0xff) << (8 * key1))); // mask out the extra bytes
         data = data.add(key1 as usize + 1);
 
-        let val: u32 = (data as *const u32).read_unaligned();
+        let val: u32 = u32::from_le((data as *const u32).read_unaligned());
         *out.add(1) = f(val & !((!0xff) << (8 * key2)));
         data = data.add(key2 as usize + 1);
 
-        let val: u32 = (data as *const u32).read_unaligned();
+        let val: u32 = u32::from_le((data as *const u32).read_unaligned());
         *out.add(2) = f(val & !((!0xff) << (8 * key3)));
         data = data.add(key3 as usize + 1);
 
-        let val: u32 = (data as *const u32).read_unaligned();
+        let val: u32 = u32::from_le((data as *const u32).read_unaligned());
         *out.add(3) = f(val & !((!0xff) << (8 * key4)));
         data = data.add(key4 as usize + 1);
         out = out.add(4);
     }
 
+    // The control stream may end right here, so don't read past it.
+    if len_remaining == 0 {
+        return (out, true);
+    }
     let mut key = *control;
     control = control.add(1);
 
@@ -106,13 +151,12 @@ where
             shift = 0;
         }
         let nbytes = ((key >> shift) & 0x3) + 1;
-        let next_data = data.add(nbytes as usize);
         // Out of bounds access?
-        if next_data > end {
+        if end.offset_from(data) < nbytes as isize {
             return (out, false);
         }
         let val = extract_bytes(data, nbytes);
-        data = next_data;
+        data = data.add(nbytes as usize);
         *out = f(val);
         out = out.add(1);
         shift += 2;
@@ -148,6 +192,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn little_endian() {
+        // Widths 1, 2, 3 and 4, written out by hand so that the test does not
+        // depend on the byte order of the host. Enough groups to go through
+        // both the unrolled loop and the tail.
+        let group = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        let values = [0x01, 0x0302, 0x06_0504, 0x0a09_0807];
+        let n = 8;
+        let mut bytes = vec![0b11_10_01_00; n];
+        for _ in 0..n {
+            bytes.extend_from_slice(&group);
+        }
+        let expected: Vec<u32> = values.iter().copied().cycle().take(4 * n).collect();
+        assert_eq!(decode(4 * n, &bytes).unwrap(), expected);
+        assert_eq!(encode(&expected), (4 * n, bytes));
+    }
+
     #[test]
     fn encode_decode_bad_length() {
         let inputs = &[
@@ -1,4 +1,4 @@
-use crate::common::{control_bytes_len, max_compressed_len};
+use crate::common::{control_bytes_len, encode_one, max_compressed_len};
 
 pub fn encode(input: &[u32]) -> (usize, Vec<u8>) {
     let items = input.len();
@@ -28,7 +28,7 @@ pub fn encode(input: &[u32]) -> (usize, Vec<u8>) {
     (items, output)
 }
 
-unsafe fn encode_worker(
+pub(crate) unsafe fn encode_worker(
     items: usize,
     mut input: *const u32,
     mut controls: *mut u8,
@@ -50,23 +50,24 @@ unsafe fn encode_worker(
 
         let symbol1 = encode_one(word1);
         key |= symbol1;
-        // Use copy_nonoverlapping because we're doing unaligned writes.
-        std::ptr::copy_nonoverlapping(input as *const u8, data, 4);
+        // Use copy_nonoverlapping because we're doing unaligned writes. The
+        // format is little-endian whatever the host is.
+        std::ptr::copy_nonoverlapping(word1.to_le_bytes().as_ptr(), data, 4);
         data = data.add(symbol1 as usize + 1);
 
         let symbol2 = encode_one(word2);
         key |= symbol2 << 2;
-        std::ptr::copy_nonoverlapping(input.add(1) as *const u8, data, 4);
+        std::ptr::copy_nonoverlapping(word2.to_le_bytes().as_ptr(), data, 4);
         data = data.add(symbol2 as usize + 1);
 
         let symbol3 = encode_one(word3);
         key |= symbol3 << 4;
-        std::ptr::copy_nonoverlapping(input.add(2) as *const u8, data, 4);
+        std::ptr::copy_nonoverlapping(word3.to_le_bytes().as_ptr(), data, 4);
         data = data.add(symbol3 as usize + 1);
 
         let symbol4 = encode_one(word4);
         key |= symbol4 << 6;
-        std::ptr::copy_nonoverlapping(input.add(3) as *const u8, data, 4);
+        std::ptr::copy_nonoverlapping(word4.to_le_bytes().as_ptr(), data, 4);
         data = data.add(symbol4 as usize + 1);
 
         input = input.add(4);
@@ -81,7 +82,7 @@ unsafe fn encode_worker(
             let word = *input;
             let symbol = encode_one(word);
             key |= symbol << (i + i);
-            std::ptr::copy_nonoverlapping(input as *const u8, data, 4);
+            std::ptr::copy_nonoverlapping(word.to_le_bytes().as_ptr(), data, 4);
             input = input.add(1);
             data = data.add(symbol as usize + 1);
         }
@@ -90,13 +91,6 @@ unsafe fn encode_worker(
     data
 }
 
-fn encode_one(word: u32) -> u32 {
-    let t1 = (word > 0x000000ff) as u32;
-    let t2 = (word > 0x0000ffff) as u32;
-    let t3 = (word > 0x00ffffff) as u32;
-    t1 + t2 + t3
-}
-
 #[cfg(test)]
 mod tests {
     use super::encode;
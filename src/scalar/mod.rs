@@ -1,5 +1,13 @@
+#[cfg(not(feature = "safe-only"))]
 pub(crate) mod decode;
+#[cfg(not(feature = "safe-only"))]
 pub(crate) mod encode;
 
-pub use decode::decode;
+#[cfg(not(feature = "safe-only"))]
+pub use decode::{decode, decode_with_options};
+#[cfg(not(feature = "safe-only"))]
 pub use encode::encode;
+
+// The scalar code uses raw pointers, so it is replaced by the safe backend.
+#[cfg(feature = "safe-only")]
+pub use crate::safe::{decode, decode_with_options, encode};
@@ -0,0 +1,219 @@
+//! Serde helpers for compact serialization of integer vectors.
+//!
+//! Use with `#[serde(with = "streamvb::serde")]` on `Vec<u32>` and `Vec<i32>`
+//! fields. A vector is written as a `(u64, bytes)` tuple holding the number of
+//! values and their StreamVByte encoding. Signed values are zigzag encoded
+//! first, so small negative numbers stay small.
+//!
+//! The encoded bytes are written with `serialize_bytes`. Formats that support
+//! it (e.g. bincode or postcard) hand them back as a borrowed slice, so
+//! deserializing does not copy them before decoding.
+//!
+//! ```
+//! # use serde_derive::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Message {
+//!     #[serde(with = "streamvb::serde")]
+//!     ids: Vec<u32>,
+//!     #[serde(with = "streamvb::serde")]
+//!     offsets: Vec<i32>,
+//! }
+//!
+//! let message = Message { ids: vec![1, 2, 300], offsets: vec![-1, 0, 70000] };
+//! let bytes = bincode::serialize(&message).unwrap();
+//! assert_eq!(bincode::deserialize::<Message>(&bytes).unwrap(), message);
+//! ```
+use std::{borrow::Cow, convert::TryFrom, fmt, marker::PhantomData};
+
+use ::serde::{
+    de::{self, SeqAccess, Visitor},
+    ser::SerializeTuple,
+    Deserializer, Serializer,
+};
+
+use crate::common::{zigzag_decode_1, zigzag_encode_1, StreamVbyteError};
+
+/// Vector types that can be serialized with this module.
+pub trait Values: Sized {
+    /// Encode into `(len, bytes)`.
+    fn encode(&self) -> (usize, Vec<u8>);
+    /// Decode `len` values from `input`.
+    fn decode(len: usize, input: &[u8]) -> Result<Self, StreamVbyteError>;
+}
+
+impl Values for Vec<u32> {
+    fn encode(&self) -> (usize, Vec<u8>) {
+        crate::encode(self)
+    }
+
+    fn decode(len: usize, input: &[u8]) -> Result<Self, StreamVbyteError> {
+        crate::decode(len, input)
+    }
+}
+
+impl Values for Vec<i32> {
+    fn encode(&self) -> (usize, Vec<u8>) {
+        let zigzag: Vec<u32> = self.iter().map(|&x| zigzag_encode_1(x as u32)).collect();
+        crate::encode(&zigzag)
+    }
+
+    fn decode(len: usize, input: &[u8]) -> Result<Self, StreamVbyteError> {
+        let values = crate::decode(len, input)?;
+        Ok(values
+            .into_iter()
+            .map(|x| zigzag_decode_1(x) as i32)
+            .collect())
+    }
+}
+
+/// Serialize `values` as their length followed by the encoded bytes.
+pub fn serialize<T, S>(values: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Values,
+    S: Serializer,
+{
+    let (len, bytes) = values.encode();
+    let mut tuple = serializer.serialize_tuple(2)?;
+    tuple.serialize_element(&(len as u64))?;
+    tuple.serialize_element(&Bytes(&bytes))?;
+    tuple.end()
+}
+
+/// Deserialize values written by [serialize], using the bounds-checked
+/// decoder.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Values,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_tuple(2, EncodedVisitor(PhantomData))
+}
+
+// Forces `serialize_bytes` instead of serializing a sequence of `u8`.
+struct Bytes<'a>(&'a [u8]);
+
+impl ::serde::Serialize for Bytes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+// Borrows the bytes from the input if the format allows it, and copies them
+// otherwise (e.g. formats that represent bytes as a sequence).
+struct BytesBuf<'de>(Cow<'de, [u8]>);
+
+impl<'de> ::serde::Deserialize<'de> for BytesBuf<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = BytesBuf<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("StreamVByte encoded bytes")
+    }
+
+    fn visit_borrowed_bytes<E: de::Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        Ok(BytesBuf(Cow::Borrowed(v)))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(BytesBuf(Cow::Owned(v.to_vec())))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(BytesBuf(Cow::Owned(v)))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        // Formats with a length prefix take the hint from the input, so it is
+        // capped to avoid a huge allocation before any byte was read.
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(1 << 16));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(BytesBuf(Cow::Owned(bytes)))
+    }
+}
+
+struct EncodedVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Values> Visitor<'de> for EncodedVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a tuple of length and StreamVByte encoded bytes")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let len: u64 = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let bytes: BytesBuf = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let len = usize::try_from(len)
+            .map_err(|_| de::Error::custom("encoded length does not fit into usize"))?;
+        T::decode(len, &bytes.0).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Message {
+        #[serde(with = "crate::serde")]
+        unsigned: Vec<u32>,
+        #[serde(with = "crate::serde")]
+        signed: Vec<i32>,
+    }
+
+    fn sample() -> Message {
+        Message {
+            unsigned: (0..1000).map(|x| x * x).collect(),
+            signed: (-500..500)
+                .map(|x| x * 7)
+                .chain([i32::MIN, i32::MAX])
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn bincode_roundtrip() {
+        let message = sample();
+        let bytes = bincode::serialize(&message).unwrap();
+        let raw = bincode::serialize(&(&message.unsigned, &message.signed)).unwrap();
+        assert!(bytes.len() < raw.len() * 3 / 4);
+        assert_eq!(bincode::deserialize::<Message>(&bytes).unwrap(), message);
+
+        let empty = Message {
+            unsigned: vec![],
+            signed: vec![],
+        };
+        let bytes = bincode::serialize(&empty).unwrap();
+        assert_eq!(bincode::deserialize::<Message>(&bytes).unwrap(), empty);
+    }
+
+    #[test]
+    fn self_describing_roundtrip() {
+        let message = sample();
+        let json = serde_json::to_string(&message).unwrap();
+        assert_eq!(serde_json::from_str::<Message>(&json).unwrap(), message);
+    }
+
+    #[test]
+    fn corrupt_input() {
+        let bytes = bincode::serialize(&sample()).unwrap();
+        // The first field starts with its u64 length; claim more values.
+        let mut corrupt = bytes.clone();
+        corrupt[0..8].copy_from_slice(&100_000u64.to_le_bytes());
+        assert!(bincode::deserialize::<Message>(&corrupt).is_err());
+        assert!(bincode::deserialize::<Message>(&bytes[..bytes.len() / 2]).is_err());
+    }
+}
@@ -5,22 +5,27 @@
 //! Note that the encoding functions from the main module will still use SIMD
 //! if available.
 use crate::common::StreamVbyteError;
+use crate::options::DecodeOptions;
 
 #[allow(clippy::needless_return)]
 pub fn decode(len: usize, input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
     #[cfg(all(
         any(target_arch = "x86", target_arch = "x86_64"),
-        target_feature = "ssse3"
+        target_feature = "ssse3",
+        not(feature = "safe-only")
     ))]
     {
         // println!("Using x86-64 simd");
         return crate::x86_64::decode::decode_simd::<crate::x86_64::decode::NoDecode>(len, input);
     }
 
-    #[cfg(all(target_arch = "aarch64", feature = "aarch64-simd"))]
+    #[cfg(all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ))]
     {
-        // println!("Using aarch64 simd");
-        return crate::aarch64::decode::decode_simd(len, input);
+        return crate::aarch64::decode::decode_simd::<crate::aarch64::decode::NoDecode>(len, input);
     }
 }
 
@@ -32,7 +37,8 @@ pub fn decode_into(
 ) -> Result<(), StreamVbyteError> {
     #[cfg(all(
         any(target_arch = "x86", target_arch = "x86_64"),
-        target_feature = "ssse3"
+        target_feature = "ssse3",
+        not(feature = "safe-only")
     ))]
     {
         // println!("Using x86-64 simd");
@@ -41,10 +47,15 @@ pub fn decode_into(
         );
     }
 
-    #[cfg(all(target_arch = "aarch64", feature = "aarch64-simd"))]
+    #[cfg(all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ))]
     {
-        // println!("Using aarch64 simd");
-        return crate::aarch64::decode::decode_simd(len, input);
+        return crate::aarch64::decode::decode_into_simd::<crate::aarch64::decode::NoDecode>(
+            len, input, output,
+        );
     }
 }
 
@@ -56,7 +67,8 @@ pub fn zigzag_decode_into(
 ) -> Result<(), StreamVbyteError> {
     #[cfg(all(
         any(target_arch = "x86", target_arch = "x86_64"),
-        target_feature = "ssse3"
+        target_feature = "ssse3",
+        not(feature = "safe-only")
     ))]
     {
         // println!("Using x86-64 simd");
@@ -65,28 +77,114 @@ pub fn zigzag_decode_into(
         );
     }
 
-    #[cfg(all(target_arch = "aarch64", feature = "aarch64-simd"))]
+    #[cfg(all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ))]
+    {
+        return crate::aarch64::decode::decode_into_simd::<crate::aarch64::decode::ZigZagDecode>(
+            len, input, output,
+        );
+    }
+}
+
+/// Like [decode], but applies the limits and checks in `options`.
+#[allow(clippy::needless_return)]
+pub fn decode_with_options(
+    len: usize,
+    input: &[u8],
+    options: &DecodeOptions,
+) -> Result<Vec<u32>, StreamVbyteError> {
+    let mut output = Vec::new();
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    ))]
+    {
+        options.decode_parts_into(
+            len,
+            input,
+            &mut output,
+            crate::x86_64::decode::decode_parts_into_simd::<crate::x86_64::decode::NoDecode>,
+        )?;
+    }
+
+    #[cfg(all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ))]
+    {
+        options.decode_parts_into(
+            len,
+            input,
+            &mut output,
+            crate::aarch64::decode::decode_parts_into_simd::<crate::aarch64::decode::NoDecode>,
+        )?;
+    }
+    Ok(output)
+}
+
+/// Like [zigzag_decode_into], but applies the limits and checks in `options`
+/// to the encoded values.
+#[allow(clippy::needless_return)]
+pub fn zigzag_decode_with_options(
+    len: usize,
+    input: &[u8],
+    options: &DecodeOptions,
+) -> Result<Vec<u32>, StreamVbyteError> {
+    let mut output = Vec::new();
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    ))]
     {
-        // println!("Using aarch64 simd");
-        return crate::aarch64::decode::decode_simd(len, input);
+        options.decode_parts_into(
+            len,
+            input,
+            &mut output,
+            crate::x86_64::decode::decode_parts_into_simd::<crate::x86_64::decode::ZigZagDecode>,
+        )?;
     }
+
+    #[cfg(all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ))]
+    {
+        options.decode_parts_into(
+            len,
+            input,
+            &mut output,
+            crate::aarch64::decode::decode_parts_into_simd::<crate::aarch64::decode::ZigZagDecode>,
+        )?;
+    }
+    Ok(output)
 }
 
 #[allow(clippy::needless_return)]
 pub fn encode(input: &[u32]) -> (usize, Vec<u8>) {
     #[cfg(all(
         any(target_arch = "x86", target_arch = "x86_64"),
-        target_feature = "ssse3"
+        target_feature = "ssse3",
+        not(feature = "safe-only")
     ))]
     {
         // println!("Using x86-64 simd");
         return crate::x86_64::encode::encode_simd::<crate::x86_64::encode::NoEncode>(input);
     }
 
-    #[cfg(all(target_arch = "aarch64", feature = "aarch64-simd"))]
+    #[cfg(all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ))]
     {
-        // println!("Using aarch64 simd");
-        return crate::aarch64::encode::encode_simd(input);
+        return crate::aarch64::encode::encode_simd::<crate::aarch64::encode::NoEncode>(input);
     }
 }
 
@@ -94,7 +192,8 @@ pub fn encode(input: &[u32]) -> (usize, Vec<u8>) {
 pub fn encode_into(input: &[u32], output: &mut Vec<u8>) -> usize {
     #[cfg(all(
         any(target_arch = "x86", target_arch = "x86_64"),
-        target_feature = "ssse3"
+        target_feature = "ssse3",
+        not(feature = "safe-only")
     ))]
     {
         // println!("Using x86-64 simd");
@@ -103,10 +202,15 @@ pub fn encode_into(input: &[u32], output: &mut Vec<u8>) -> usize {
         );
     }
 
-    #[cfg(all(target_arch = "aarch64", feature = "aarch64-simd"))]
+    #[cfg(all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ))]
     {
-        // println!("Using aarch64 simd");
-        return crate::aarch64::encode::encode_simd(input);
+        return crate::aarch64::encode::encode_into_simd::<crate::aarch64::encode::NoEncode>(
+            input, output,
+        );
     }
 }
 
@@ -114,7 +218,8 @@ pub fn encode_into(input: &[u32], output: &mut Vec<u8>) -> usize {
 pub fn zigzag_encode_into(input: &[u32], output: &mut Vec<u8>) -> usize {
     #[cfg(all(
         any(target_arch = "x86", target_arch = "x86_64"),
-        target_feature = "ssse3"
+        target_feature = "ssse3",
+        not(feature = "safe-only")
     ))]
     {
         // println!("Using x86-64 simd");
@@ -123,9 +228,14 @@ pub fn zigzag_encode_into(input: &[u32], output: &mut Vec<u8>) -> usize {
         );
     }
 
-    #[cfg(all(target_arch = "aarch64", feature = "aarch64-simd"))]
+    #[cfg(all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ))]
     {
-        // println!("Using aarch64 simd");
-        return crate::aarch64::encode::encode_simd(input);
+        return crate::aarch64::encode::encode_into_simd::<crate::aarch64::encode::ZigZagEncode>(
+            input, output,
+        );
     }
 }
@@ -0,0 +1,259 @@
+//! Joining and splitting encoded streams without decoding them.
+//!
+//! The data bytes are only ever copied; just the 2-bit codes in the control
+//! stream need to be re-laid when a boundary falls inside a control byte.
+use std::ops::Range;
+
+use crate::common::{control_bytes_len, data_bytes_len, StreamVbyteError};
+
+/// Concatenate two encoded streams, each given as `(len, bytes)` as returned
+/// by [crate::encode]. The result is identical to encoding the concatenated
+/// values.
+///
+/// Returns an error if either input is too short for its length.
+///
+/// ```
+/// let a = streamvb::encode(&[1, 300, 70000]);
+/// let b = streamvb::encode(&[5, 0xdeadbeef]);
+/// let ab = streamvb::splice::concat((a.0, &a.1), (b.0, &b.1)).unwrap();
+/// assert_eq!(ab, streamvb::encode(&[1, 300, 70000, 5, 0xdeadbeef]));
+/// ```
+pub fn concat(a: (usize, &[u8]), b: (usize, &[u8])) -> Result<(usize, Vec<u8>), StreamVbyteError> {
+    let (control_a, data_a) = split_streams(a.0, a.1)?;
+    let (control_b, data_b) = split_streams(b.0, b.1)?;
+    let len = a.0 + b.0;
+    let num_controls = control_bytes_len(len);
+    let mut output = Vec::with_capacity(num_controls + data_a.len() + data_b.len());
+    append_codes(&mut output, 0, control_a, 0, a.0);
+    append_codes(&mut output, a.0, control_b, 0, b.0);
+    debug_assert_eq!(output.len(), num_controls);
+    output.extend_from_slice(data_a);
+    output.extend_from_slice(data_b);
+    Ok((len, output))
+}
+
+/// Split an encoded stream of `len` values into two standalone encodings of
+/// the first `k` and the remaining `len - k` values.
+///
+/// Returns an error if `input` is too short for `len` values. Panics if `k`
+/// is larger than `len`.
+///
+/// ```
+/// let (len, bytes) = streamvb::encode(&[1, 300, 70000, 5, 0xdeadbeef]);
+/// let (a, b) = streamvb::splice::split_at(len, &bytes, 2).unwrap();
+/// assert_eq!(a, streamvb::encode(&[1, 300]));
+/// assert_eq!(b, streamvb::encode(&[70000, 5, 0xdeadbeef]));
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn split_at(
+    len: usize,
+    input: &[u8],
+    k: usize,
+) -> Result<((usize, Vec<u8>), (usize, Vec<u8>)), StreamVbyteError> {
+    assert!(k <= len, "split index out of bounds");
+    let (control, data) = split_streams(len, input)?;
+    let offset = data_bytes_len(control, k);
+    Ok((
+        extract(control, 0..k, &data[..offset]),
+        extract(control, k..len, &data[offset..]),
+    ))
+}
+
+/// Extract the values in `range` from an encoded stream of `len` values as a
+/// standalone encoding.
+///
+/// Returns an error if `input` is too short for `len` values. Panics if
+/// `range` is out of bounds.
+///
+/// ```
+/// let (len, bytes) = streamvb::encode(&[1, 300, 70000, 5, 0xdeadbeef]);
+/// let page = streamvb::splice::slice(len, &bytes, 1..4).unwrap();
+/// assert_eq!(page, streamvb::encode(&[300, 70000, 5]));
+/// ```
+pub fn slice(
+    len: usize,
+    input: &[u8],
+    range: Range<usize>,
+) -> Result<(usize, Vec<u8>), StreamVbyteError> {
+    assert!(
+        range.start <= range.end && range.end <= len,
+        "slice range out of bounds"
+    );
+    let (control, data) = split_streams(len, input)?;
+    let start = data_bytes_len(control, range.start);
+    let end = start + codes_data_len(control, range.clone());
+    Ok(extract(control, range, &data[start..end]))
+}
+
+// Build a standalone encoding of the codes in `range` and their `data`.
+fn extract(control: &[u8], range: Range<usize>, data: &[u8]) -> (usize, Vec<u8>) {
+    let count = range.end - range.start;
+    let mut output = Vec::with_capacity(control_bytes_len(count) + data.len());
+    append_codes(&mut output, 0, control, range.start, count);
+    output.extend_from_slice(data);
+    (count, output)
+}
+
+// Number of data bytes used by the codes in `range`.
+fn codes_data_len(control: &[u8], range: Range<usize>) -> usize {
+    let mut total = 0;
+    let mut i = range.start;
+    // Single codes up to the next control byte boundary, then whole bytes.
+    while i < range.end && !i.is_multiple_of(4) {
+        total += ((control[i / 4] >> (2 * (i % 4))) & 0x3) as usize + 1;
+        i += 1;
+    }
+    total + data_bytes_len(&control[i / 4..], range.end - i)
+}
+
+// Split an encoding into its control bytes and exactly the data bytes used.
+fn split_streams(len: usize, input: &[u8]) -> Result<(&[u8], &[u8]), StreamVbyteError> {
+    let num_controls = control_bytes_len(len);
+    if num_controls > input.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    let (control, data) = input.split_at(num_controls);
+    let data_len = data_bytes_len(control, len);
+    if data_len > data.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    Ok((control, &data[..data_len]))
+}
+
+// Append `count` codes from `control`, starting at code index `start`, to
+// `output`, which holds the control bytes for `output_len` codes. Unused bits
+// in the last control byte are left zero, like the encoder does.
+fn append_codes(
+    output: &mut Vec<u8>,
+    output_len: usize,
+    control: &[u8],
+    start: usize,
+    count: usize,
+) {
+    debug_assert_eq!(output.len(), control_bytes_len(output_len));
+    if count == 0 {
+        return;
+    }
+    let src = &control[start / 4..control_bytes_len(start + count)];
+    let src_shift = 2 * (start % 4);
+    let mut nbits = 2 * (output_len % 4);
+
+    if nbits == 0 && src_shift == 0 {
+        // Byte aligned on both ends.
+        output.extend_from_slice(src);
+    } else {
+        // `acc` holds `nbits` pending bits that do not fill a byte yet.
+        let mut acc: u32 = if nbits > 0 {
+            output.pop().unwrap() as u32 & low_bits(nbits)
+        } else {
+            0
+        };
+        let mut remaining = 2 * count;
+        let mut shift = src_shift;
+        for &byte in src {
+            let bits = (8 - shift).min(remaining);
+            acc |= ((byte as u32 >> shift) & low_bits(bits)) << nbits;
+            nbits += bits;
+            remaining -= bits;
+            shift = 0;
+            if nbits >= 8 {
+                output.push(acc as u8);
+                acc >>= 8;
+                nbits -= 8;
+            }
+        }
+        if nbits > 0 {
+            output.push(acc as u8);
+        }
+    }
+    let tail = (output_len + count) % 4;
+    if tail != 0 {
+        *output.last_mut().unwrap() &= low_bits(2 * tail) as u8;
+    }
+}
+
+#[inline]
+fn low_bits(bits: usize) -> u32 {
+    (1 << bits) - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{concat, slice, split_at};
+
+    type EncodeFn = fn(&[u32]) -> (usize, Vec<u8>);
+
+    fn encoders() -> Vec<(&'static str, EncodeFn)> {
+        #[allow(unused_mut)]
+        let mut encoders: Vec<(&str, EncodeFn)> = vec![
+            ("scalar", crate::scalar::encode),
+            ("safe", crate::safe::encode),
+            ("default", crate::encode),
+        ];
+        #[cfg(all(
+            any(target_arch = "x86", target_arch = "x86_64"),
+            target_feature = "ssse3",
+            not(feature = "safe-only")
+        ))]
+        encoders.push((
+            "ssse3",
+            crate::x86_64::encode::encode_simd::<crate::x86_64::encode::NoEncode>,
+        ));
+        #[cfg(all(
+            target_arch = "aarch64",
+            feature = "aarch64-simd",
+            not(feature = "safe-only")
+        ))]
+        encoders.push((
+            "neon",
+            crate::aarch64::encode::encode_simd::<crate::aarch64::encode::NoEncode>,
+        ));
+        encoders
+    }
+
+    #[test]
+    fn concat_matches_encode() {
+        let values = crate::common::sample_values(100);
+        for (name, encode) in encoders() {
+            for len_a in 0..13 {
+                for len_b in [0, 1, 2, 3, 4, 5, 7, 8, 9, 50, 87] {
+                    let a = &values[..len_a];
+                    let b = &values[len_a..len_a + len_b];
+                    let (na, ea) = encode(a);
+                    let (nb, eb) = encode(b);
+                    let joined = concat((na, &ea), (nb, &eb)).unwrap();
+                    assert_eq!(joined, encode(&values[..len_a + len_b]), "{}", name);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn concat_errors() {
+        let (len, bytes) = crate::encode(&[1, 300, 70000]);
+        assert!(concat((len, &bytes[..2]), (len, &bytes)).is_err());
+        assert!(concat((len, &bytes), (len + 1, &bytes)).is_err());
+    }
+
+    #[test]
+    fn split_and_slice() {
+        let values = crate::common::sample_values(40);
+        let (len, bytes) = crate::encode(&values);
+        for k in 0..=len {
+            let (a, b) = split_at(len, &bytes, k).unwrap();
+            assert_eq!(a, crate::encode(&values[..k]));
+            assert_eq!(b, crate::encode(&values[k..]));
+            assert_eq!(
+                concat((a.0, &a.1), (b.0, &b.1)).unwrap(),
+                (len, bytes.clone())
+            );
+            for end in k..=len {
+                assert_eq!(
+                    slice(len, &bytes, k..end).unwrap(),
+                    crate::encode(&values[k..end])
+                );
+            }
+        }
+        assert!(slice(len, &bytes[..len / 4], 0..1).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+//! Compression statistics, computed either from encoded bytes or from the
+//! values before encoding.
+use crate::{
+    common::{control_bytes_len, StreamVbyteError},
+    tables::len::WIDTH_COUNT_TABLE,
+};
+
+/// Histogram of the encoded value widths.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Number of values using 1, 2, 3 and 4 bytes, respectively.
+    pub widths: [usize; 4],
+    /// Total number of data bytes (excluding control bytes).
+    pub data_bytes: usize,
+}
+
+impl Stats {
+    /// Number of encoded values.
+    pub fn len(&self) -> usize {
+        self.widths.iter().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Total encoded size in bytes, including control bytes.
+    pub fn compressed_len(&self) -> usize {
+        control_bytes_len(self.len()) + self.data_bytes
+    }
+
+    /// Encoded size relative to the size of the raw `u32` values. Values below
+    /// 1.0 mean the encoding saves space. Returns 1.0 for empty inputs.
+    pub fn ratio(&self) -> f64 {
+        if self.is_empty() {
+            return 1.0;
+        }
+        self.compressed_len() as f64 / (self.len() as f64 * 4.0)
+    }
+
+    fn from_widths(widths: [usize; 4]) -> Self {
+        let data_bytes = widths[0] + 2 * widths[1] + 3 * widths[2] + 4 * widths[3];
+        Stats { widths, data_bytes }
+    }
+}
+
+/// Compute [Stats] for an encoded stream of `len` values. Only reads the
+/// control bytes.
+///
+/// Returns an error if `input` is too short for the control and data bytes
+/// described by the control bytes.
+///
+/// ```
+/// let (len, bytes) = streamvb::encode(&[1, 2, 300, 70000, 0xdeadbeef]);
+/// let stats = streamvb::stats::stats(len, &bytes).unwrap();
+/// assert_eq!(stats.widths, [2, 1, 1, 1]);
+/// assert_eq!(stats.data_bytes, 11);
+/// assert_eq!(stats.compressed_len(), bytes.len());
+/// ```
+pub fn stats(len: usize, input: &[u8]) -> Result<Stats, StreamVbyteError> {
+    let num_control_bytes = control_bytes_len(len);
+    if num_control_bytes > input.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    let control = &input[..num_control_bytes];
+    let full_controls = len / 4;
+
+    let mut widths = [0usize; 4];
+    // Sum up the packed counts in chunks small enough to not overflow a lane.
+    for chunk in control[..full_controls].chunks(63) {
+        let packed: u32 = chunk
+            .iter()
+            .map(|&key| WIDTH_COUNT_TABLE[key as usize])
+            .sum();
+        for (i, width) in widths.iter_mut().enumerate() {
+            *width += ((packed >> (8 * i)) & 0xff) as usize;
+        }
+    }
+    for i in 0..len % 4 {
+        let code = (control[full_controls] >> (2 * i)) & 0x3;
+        widths[code as usize] += 1;
+    }
+
+    let stats = Stats::from_widths(widths);
+    if stats.compressed_len() > input.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    Ok(stats)
+}
+
+/// Compute the [Stats] that encoding `values` would produce, without encoding.
+pub fn stats_of(values: &[u32]) -> Stats {
+    let mut widths = [0usize; 4];
+    for &value in values {
+        let t1 = (value > 0x000000ff) as usize;
+        let t2 = (value > 0x0000ffff) as usize;
+        let t3 = (value > 0x00ffffff) as usize;
+        widths[t1 + t2 + t3] += 1;
+    }
+    Stats::from_widths(widths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{stats, stats_of, Stats};
+
+    #[test]
+    fn stats_match() {
+        let inputs: &[Vec<u32>] = &[
+            vec![],
+            vec![7],
+            vec![255, 256, 65535, 65536, 0xffffff, 0x1000000],
+            (0..1001).map(|x| x * x * 17).collect(),
+        ];
+        for input in inputs {
+            let (len, bytes) = crate::encode(input);
+            let from_bytes = stats(len, &bytes).unwrap();
+            assert_eq!(from_bytes, stats_of(input));
+            assert_eq!(from_bytes.len(), input.len());
+            assert_eq!(from_bytes.compressed_len(), bytes.len());
+            assert_eq!(from_bytes.data_bytes, crate::exact_compressed_len(input));
+        }
+    }
+
+    #[test]
+    fn ratio() {
+        assert_eq!(Stats::default().ratio(), 1.0);
+        // 1 control byte + 4 data bytes for 16 bytes of input.
+        assert_eq!(stats_of(&[1, 2, 3, 4]).ratio(), 5.0 / 16.0);
+    }
+
+    #[test]
+    fn truncated() {
+        let (len, bytes) = crate::encode(&[1, 2, 300, 70000, 0xdeadbeef]);
+        assert!(stats(len, &bytes[..bytes.len() - 1]).is_err());
+        assert!(stats(len, &bytes[..1]).is_err());
+    }
+}
@@ -37,3 +37,65 @@ pub static LENGTH_TABLE: [u8; 256] = [
      9, 10, 11, 12, 10, 11, 12, 13, 11, 12, 13, 14, 12, 13, 14, 15, 
     10, 11, 12, 13, 11, 12, 13, 14, 12, 13, 14, 15, 13, 14, 15, 16, 
 ];
+
+// Each entry packs the number of values using 1, 2, 3 and 4 bytes into the
+// bytes of a `u32` (1-byte count in the lowest byte). Entries can be summed
+// up directly as long as no lane exceeds 255, i.e., for up to 63 entries.
+#[cfg(test)]
+#[test]
+fn build_width_count_table() {
+    println!("#[rustfmt::skip]");
+    println!("pub static WIDTH_COUNT_TABLE: [u32; 256] = [");
+    let mut entries = Vec::new();
+    for b0 in 1..5 {
+        for b1 in 1..5 {
+            for b2 in 1..5 {
+                for b3 in 1..5 {
+                    let counts: u32 = [b0, b1, b2, b3].iter().map(|b| 1 << (8 * (b - 1))).sum();
+                    entries.push(counts);
+                }
+            }
+        }
+    }
+    for row in entries.chunks(8) {
+        let row: Vec<String> = row.iter().map(|c| format!("{:#010x}", c)).collect();
+        println!("    {},", row.join(", "));
+    }
+    println!("];")
+}
+
+#[rustfmt::skip]
+pub static WIDTH_COUNT_TABLE: [u32; 256] = [
+    0x00000004, 0x00000103, 0x00010003, 0x01000003, 0x00000103, 0x00000202, 0x00010102, 0x01000102,
+    0x00010003, 0x00010102, 0x00020002, 0x01010002, 0x01000003, 0x01000102, 0x01010002, 0x02000002,
+    0x00000103, 0x00000202, 0x00010102, 0x01000102, 0x00000202, 0x00000301, 0x00010201, 0x01000201,
+    0x00010102, 0x00010201, 0x00020101, 0x01010101, 0x01000102, 0x01000201, 0x01010101, 0x02000101,
+    0x00010003, 0x00010102, 0x00020002, 0x01010002, 0x00010102, 0x00010201, 0x00020101, 0x01010101,
+    0x00020002, 0x00020101, 0x00030001, 0x01020001, 0x01010002, 0x01010101, 0x01020001, 0x02010001,
+    0x01000003, 0x01000102, 0x01010002, 0x02000002, 0x01000102, 0x01000201, 0x01010101, 0x02000101,
+    0x01010002, 0x01010101, 0x01020001, 0x02010001, 0x02000002, 0x02000101, 0x02010001, 0x03000001,
+    0x00000103, 0x00000202, 0x00010102, 0x01000102, 0x00000202, 0x00000301, 0x00010201, 0x01000201,
+    0x00010102, 0x00010201, 0x00020101, 0x01010101, 0x01000102, 0x01000201, 0x01010101, 0x02000101,
+    0x00000202, 0x00000301, 0x00010201, 0x01000201, 0x00000301, 0x00000400, 0x00010300, 0x01000300,
+    0x00010201, 0x00010300, 0x00020200, 0x01010200, 0x01000201, 0x01000300, 0x01010200, 0x02000200,
+    0x00010102, 0x00010201, 0x00020101, 0x01010101, 0x00010201, 0x00010300, 0x00020200, 0x01010200,
+    0x00020101, 0x00020200, 0x00030100, 0x01020100, 0x01010101, 0x01010200, 0x01020100, 0x02010100,
+    0x01000102, 0x01000201, 0x01010101, 0x02000101, 0x01000201, 0x01000300, 0x01010200, 0x02000200,
+    0x01010101, 0x01010200, 0x01020100, 0x02010100, 0x02000101, 0x02000200, 0x02010100, 0x03000100,
+    0x00010003, 0x00010102, 0x00020002, 0x01010002, 0x00010102, 0x00010201, 0x00020101, 0x01010101,
+    0x00020002, 0x00020101, 0x00030001, 0x01020001, 0x01010002, 0x01010101, 0x01020001, 0x02010001,
+    0x00010102, 0x00010201, 0x00020101, 0x01010101, 0x00010201, 0x00010300, 0x00020200, 0x01010200,
+    0x00020101, 0x00020200, 0x00030100, 0x01020100, 0x01010101, 0x01010200, 0x01020100, 0x02010100,
+    0x00020002, 0x00020101, 0x00030001, 0x01020001, 0x00020101, 0x00020200, 0x00030100, 0x01020100,
+    0x00030001, 0x00030100, 0x00040000, 0x01030000, 0x01020001, 0x01020100, 0x01030000, 0x02020000,
+    0x01010002, 0x01010101, 0x01020001, 0x02010001, 0x01010101, 0x01010200, 0x01020100, 0x02010100,
+    0x01020001, 0x01020100, 0x01030000, 0x02020000, 0x02010001, 0x02010100, 0x02020000, 0x03010000,
+    0x01000003, 0x01000102, 0x01010002, 0x02000002, 0x01000102, 0x01000201, 0x01010101, 0x02000101,
+    0x01010002, 0x01010101, 0x01020001, 0x02010001, 0x02000002, 0x02000101, 0x02010001, 0x03000001,
+    0x01000102, 0x01000201, 0x01010101, 0x02000101, 0x01000201, 0x01000300, 0x01010200, 0x02000200,
+    0x01010101, 0x01010200, 0x01020100, 0x02010100, 0x02000101, 0x02000200, 0x02010100, 0x03000100,
+    0x01010002, 0x01010101, 0x01020001, 0x02010001, 0x01010101, 0x01010200, 0x01020100, 0x02010100,
+    0x01020001, 0x01020100, 0x01030000, 0x02020000, 0x02010001, 0x02010100, 0x02020000, 0x03010000,
+    0x02000002, 0x02000101, 0x02010001, 0x03000001, 0x02000101, 0x02000200, 0x02010100, 0x03000100,
+    0x02010001, 0x02010100, 0x02020000, 0x03010000, 0x03000001, 0x03000100, 0x03010000, 0x04000000,
+];
@@ -1,2 +1,4 @@
 pub(crate) mod len;
 pub(crate) mod shuffle;
+pub(crate) mod varint;
+pub(crate) mod zero124;
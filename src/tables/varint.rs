@@ -0,0 +1,376 @@
+// Tables for the SIMD LEB128 decoder. The index is the continuation bits (the
+// high bits) of the next 8 input bytes. An entry covers the varints that lie
+// completely within those 8 bytes, at most 4 of them and each at most 4 bytes
+// long. The shuffle moves the bytes of the i-th varint to the i-th u32.
+//
+// Example:
+//
+// continuation bits: 0b0000_0010  =>  sizes: 1|2|1|1
+//
+// shuffle mask: [0, z, z, z, 1, 2, z, z, 3, z, z, z, 4, z, z, z]
+// step: 4 values, 5 bytes
+//
+// If the first varint is longer than 4 bytes, the entry is empty (0 values).
+#[cfg(test)]
+fn varint_step(mask: u8) -> ([u8; 16], u8, u8) {
+    let mut shuf = [0xff_u8; 16];
+    let mut count = 0;
+    let mut start = 0;
+    while count < 4 {
+        let mut end = start;
+        while end < 8 && mask & (1 << end) != 0 {
+            end += 1;
+        }
+        let size = end + 1 - start;
+        if end >= 8 || size > 4 {
+            break;
+        }
+        for i in 0..size {
+            shuf[4 * count + i] = (start + i) as u8;
+        }
+        count += 1;
+        start = end + 1;
+    }
+    (shuf, count as u8, start as u8)
+}
+
+#[cfg(test)]
+#[test]
+fn build_varint_tables() {
+    println!("#[rustfmt::skip]");
+    println!("pub static VARINT_SHUFFLE_TABLE: [[u8; 16]; 256] = [");
+    for mask in 0..=255u8 {
+        let (shuf, _, _) = varint_step(mask);
+        print!("    [");
+        for b in shuf {
+            if b < 0x80 {
+                print!("{:4}, ", b);
+            } else {
+                print!("0xff, ");
+            }
+        }
+        println!("],  // {:08b}", mask);
+    }
+    println!("];");
+    println!();
+    println!("#[rustfmt::skip]");
+    println!("pub static VARINT_STEP_TABLE: [(u8, u8); 256] = [");
+    for row in 0..32u8 {
+        print!("   ");
+        for col in 0..8u8 {
+            let (_, count, bytes) = varint_step(8 * row + col);
+            print!(" ({}, {}),", count, bytes);
+        }
+        println!();
+    }
+    println!("];")
+}
+
+#[cfg(test)]
+#[test]
+fn varint_tables_match() {
+    for mask in 0..=255u8 {
+        let (shuf, count, bytes) = varint_step(mask);
+        assert_eq!(VARINT_SHUFFLE_TABLE[mask as usize], shuf);
+        assert_eq!(VARINT_STEP_TABLE[mask as usize], (count, bytes));
+    }
+}
+
+// generated using code above
+#[allow(unused)]
+#[rustfmt::skip]
+pub static VARINT_SHUFFLE_TABLE: [[u8; 16]; 256] = [
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 00000000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 00000001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 00000010
+    [   0,    1,    2, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 00000011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 00000100
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 00000101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3, 0xff,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 00000110
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 00000111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff, ],  // 00001000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 00001001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 00001010
+    [   0,    1,    2, 0xff,    3,    4, 0xff, 0xff,    5, 0xff, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 00001011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3,    4, 0xff,    5, 0xff, 0xff, 0xff, ],  // 00001100
+    [   0,    1, 0xff, 0xff,    2,    3,    4, 0xff,    5, 0xff, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 00001101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5, 0xff, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 00001110
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 00001111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 00010000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 00010001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 00010010
+    [   0,    1,    2, 0xff,    3, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 00010011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 00010100
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 00010101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3, 0xff,    4,    5, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 00010110
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff,    6, 0xff, 0xff, 0xff,    7, 0xff, 0xff, 0xff, ],  // 00010111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4,    5, 0xff, ],  // 00011000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4,    5, 0xff,    6, 0xff, 0xff, 0xff, ],  // 00011001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4,    5, 0xff,    6, 0xff, 0xff, 0xff, ],  // 00011010
+    [   0,    1,    2, 0xff,    3,    4,    5, 0xff,    6, 0xff, 0xff, 0xff,    7, 0xff, 0xff, 0xff, ],  // 00011011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3,    4,    5,    6, 0xff, 0xff, 0xff, ],  // 00011100
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5,    6, 0xff, 0xff, 0xff,    7, 0xff, 0xff, 0xff, ],  // 00011101
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 00011110
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 00011111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 00100000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 00100001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 00100010
+    [   0,    1,    2, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5,    6, 0xff, 0xff, ],  // 00100011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 00100100
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5,    6, 0xff, 0xff, ],  // 00100101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3, 0xff,    4, 0xff, 0xff, 0xff,    5,    6, 0xff, 0xff, ],  // 00100110
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5,    6, 0xff, 0xff,    7, 0xff, 0xff, 0xff, ],  // 00100111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff, ],  // 00101000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff,    5,    6, 0xff, 0xff, ],  // 00101001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4, 0xff, 0xff,    5,    6, 0xff, 0xff, ],  // 00101010
+    [   0,    1,    2, 0xff,    3,    4, 0xff, 0xff,    5,    6, 0xff, 0xff,    7, 0xff, 0xff, 0xff, ],  // 00101011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3,    4, 0xff,    5,    6, 0xff, 0xff, ],  // 00101100
+    [   0,    1, 0xff, 0xff,    2,    3,    4, 0xff,    5,    6, 0xff, 0xff,    7, 0xff, 0xff, 0xff, ],  // 00101101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5,    6, 0xff, 0xff,    7, 0xff, 0xff, 0xff, ],  // 00101110
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 00101111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 00110000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4,    5,    6, 0xff, ],  // 00110001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4,    5,    6, 0xff, ],  // 00110010
+    [   0,    1,    2, 0xff,    3, 0xff, 0xff, 0xff,    4,    5,    6, 0xff,    7, 0xff, 0xff, 0xff, ],  // 00110011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5,    6, 0xff, ],  // 00110100
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5,    6, 0xff,    7, 0xff, 0xff, 0xff, ],  // 00110101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3, 0xff,    4,    5,    6, 0xff,    7, 0xff, 0xff, 0xff, ],  // 00110110
+    [   0,    1,    2,    3,    4,    5,    6, 0xff,    7, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 00110111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4,    5,    6, ],  // 00111000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4,    5,    6,    7, 0xff, 0xff, 0xff, ],  // 00111001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4,    5,    6,    7, 0xff, 0xff, 0xff, ],  // 00111010
+    [   0,    1,    2, 0xff,    3,    4,    5,    6,    7, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 00111011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 00111100
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 00111101
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 00111110
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 00111111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 01000000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 01000001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 01000010
+    [   0,    1,    2, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 01000011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 01000100
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 01000101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3, 0xff,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 01000110
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff,    6,    7, 0xff, 0xff, ],  // 01000111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff, ],  // 01001000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 01001001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 01001010
+    [   0,    1,    2, 0xff,    3,    4, 0xff, 0xff,    5, 0xff, 0xff, 0xff,    6,    7, 0xff, 0xff, ],  // 01001011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3,    4, 0xff,    5, 0xff, 0xff, 0xff, ],  // 01001100
+    [   0,    1, 0xff, 0xff,    2,    3,    4, 0xff,    5, 0xff, 0xff, 0xff,    6,    7, 0xff, 0xff, ],  // 01001101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5, 0xff, 0xff, 0xff,    6,    7, 0xff, 0xff, ],  // 01001110
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01001111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 01010000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 01010001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 01010010
+    [   0,    1,    2, 0xff,    3, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff,    6,    7, 0xff, 0xff, ],  // 01010011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 01010100
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5, 0xff, 0xff,    6,    7, 0xff, 0xff, ],  // 01010101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3, 0xff,    4,    5, 0xff, 0xff,    6,    7, 0xff, 0xff, ],  // 01010110
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff,    6,    7, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01010111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4,    5, 0xff, ],  // 01011000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4,    5, 0xff,    6,    7, 0xff, 0xff, ],  // 01011001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4,    5, 0xff,    6,    7, 0xff, 0xff, ],  // 01011010
+    [   0,    1,    2, 0xff,    3,    4,    5, 0xff,    6,    7, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01011011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3,    4,    5,    6,    7, 0xff, 0xff, ],  // 01011100
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5,    6,    7, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01011101
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01011110
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01011111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 01100000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 01100001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 01100010
+    [   0,    1,    2, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5,    6,    7, 0xff, ],  // 01100011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 01100100
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5,    6,    7, 0xff, ],  // 01100101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3, 0xff,    4, 0xff, 0xff, 0xff,    5,    6,    7, 0xff, ],  // 01100110
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5,    6,    7, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01100111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff, ],  // 01101000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff,    5,    6,    7, 0xff, ],  // 01101001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4, 0xff, 0xff,    5,    6,    7, 0xff, ],  // 01101010
+    [   0,    1,    2, 0xff,    3,    4, 0xff, 0xff,    5,    6,    7, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01101011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3,    4, 0xff,    5,    6,    7, 0xff, ],  // 01101100
+    [   0,    1, 0xff, 0xff,    2,    3,    4, 0xff,    5,    6,    7, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01101101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5,    6,    7, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01101110
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01101111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 01110000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4,    5,    6,    7, ],  // 01110001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4,    5,    6,    7, ],  // 01110010
+    [   0,    1,    2, 0xff,    3, 0xff, 0xff, 0xff,    4,    5,    6,    7, 0xff, 0xff, 0xff, 0xff, ],  // 01110011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5,    6,    7, ],  // 01110100
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5,    6,    7, 0xff, 0xff, 0xff, 0xff, ],  // 01110101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3, 0xff,    4,    5,    6,    7, 0xff, 0xff, 0xff, 0xff, ],  // 01110110
+    [   0,    1,    2,    3,    4,    5,    6,    7, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01110111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01111000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01111001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01111010
+    [   0,    1,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01111011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01111100
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01111101
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01111110
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 01111111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 10000000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 10000001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 10000010
+    [   0,    1,    2, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 10000011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 10000100
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 10000101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3, 0xff,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 10000110
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 10000111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff, ],  // 10001000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 10001001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 10001010
+    [   0,    1,    2, 0xff,    3,    4, 0xff, 0xff,    5, 0xff, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 10001011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3,    4, 0xff,    5, 0xff, 0xff, 0xff, ],  // 10001100
+    [   0,    1, 0xff, 0xff,    2,    3,    4, 0xff,    5, 0xff, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 10001101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5, 0xff, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 10001110
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10001111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 10010000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 10010001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 10010010
+    [   0,    1,    2, 0xff,    3, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 10010011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 10010100
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 10010101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3, 0xff,    4,    5, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 10010110
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff,    6, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10010111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4,    5, 0xff, ],  // 10011000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4,    5, 0xff,    6, 0xff, 0xff, 0xff, ],  // 10011001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4,    5, 0xff,    6, 0xff, 0xff, 0xff, ],  // 10011010
+    [   0,    1,    2, 0xff,    3,    4,    5, 0xff,    6, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10011011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3,    4,    5,    6, 0xff, 0xff, 0xff, ],  // 10011100
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5,    6, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10011101
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10011110
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10011111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 10100000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 10100001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 10100010
+    [   0,    1,    2, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5,    6, 0xff, 0xff, ],  // 10100011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 10100100
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5,    6, 0xff, 0xff, ],  // 10100101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3, 0xff,    4, 0xff, 0xff, 0xff,    5,    6, 0xff, 0xff, ],  // 10100110
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5,    6, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10100111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff, ],  // 10101000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff,    5,    6, 0xff, 0xff, ],  // 10101001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4, 0xff, 0xff,    5,    6, 0xff, 0xff, ],  // 10101010
+    [   0,    1,    2, 0xff,    3,    4, 0xff, 0xff,    5,    6, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10101011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3,    4, 0xff,    5,    6, 0xff, 0xff, ],  // 10101100
+    [   0,    1, 0xff, 0xff,    2,    3,    4, 0xff,    5,    6, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10101101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5,    6, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10101110
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10101111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 10110000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4,    5,    6, 0xff, ],  // 10110001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4,    5,    6, 0xff, ],  // 10110010
+    [   0,    1,    2, 0xff,    3, 0xff, 0xff, 0xff,    4,    5,    6, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10110011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5,    6, 0xff, ],  // 10110100
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5,    6, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10110101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3, 0xff,    4,    5,    6, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10110110
+    [   0,    1,    2,    3,    4,    5,    6, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10110111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4,    5,    6, ],  // 10111000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4,    5,    6, 0xff, 0xff, 0xff, 0xff, ],  // 10111001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4,    5,    6, 0xff, 0xff, 0xff, 0xff, ],  // 10111010
+    [   0,    1,    2, 0xff,    3,    4,    5,    6, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10111011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10111100
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10111101
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10111110
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 10111111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 11000000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 11000001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 11000010
+    [   0,    1,    2, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 11000011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 11000100
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 11000101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3, 0xff,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 11000110
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11000111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff, ],  // 11001000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 11001001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 11001010
+    [   0,    1,    2, 0xff,    3,    4, 0xff, 0xff,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11001011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3,    4, 0xff,    5, 0xff, 0xff, 0xff, ],  // 11001100
+    [   0,    1, 0xff, 0xff,    2,    3,    4, 0xff,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11001101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11001110
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11001111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 11010000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 11010001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 11010010
+    [   0,    1,    2, 0xff,    3, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11010011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 11010100
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11010101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3, 0xff,    4,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11010110
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11010111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4,    5, 0xff, ],  // 11011000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4,    5, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11011001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4,    5, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11011010
+    [   0,    1,    2, 0xff,    3,    4,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11011011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3,    4,    5, 0xff, 0xff, 0xff, 0xff, ],  // 11011100
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11011101
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11011110
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11011111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 11100000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 11100001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 11100010
+    [   0,    1,    2, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11100011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 11100100
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11100101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3, 0xff,    4, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11100110
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11100111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff, ],  // 11101000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11101001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11101010
+    [   0,    1,    2, 0xff,    3,    4, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11101011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3,    4, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11101100
+    [   0,    1, 0xff, 0xff,    2,    3,    4, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11101101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11101110
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11101111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 11110000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11110001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11110010
+    [   0,    1,    2, 0xff,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11110011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11110100
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11110101
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11110110
+    [   0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11110111
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11111000
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11111001
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11111010
+    [   0,    1,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11111011
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11111100
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11111101
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11111110
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 11111111
+];
+
+#[allow(unused)]
+#[rustfmt::skip]
+pub static VARINT_STEP_TABLE: [(u8, u8); 256] = [
+    (4, 4), (4, 5), (4, 5), (4, 6), (4, 5), (4, 6), (4, 6), (4, 7),
+    (4, 5), (4, 6), (4, 6), (4, 7), (4, 6), (4, 7), (4, 7), (0, 0),
+    (4, 4), (4, 6), (4, 6), (4, 7), (4, 6), (4, 7), (4, 7), (4, 8),
+    (4, 6), (4, 7), (4, 7), (4, 8), (4, 7), (4, 8), (1, 1), (0, 0),
+    (4, 4), (4, 5), (4, 5), (4, 7), (4, 5), (4, 7), (4, 7), (4, 8),
+    (4, 5), (4, 7), (4, 7), (4, 8), (4, 7), (4, 8), (4, 8), (0, 0),
+    (4, 4), (4, 7), (4, 7), (4, 8), (4, 7), (4, 8), (4, 8), (3, 8),
+    (4, 7), (4, 8), (4, 8), (3, 8), (2, 2), (1, 2), (1, 1), (0, 0),
+    (4, 4), (4, 5), (4, 5), (4, 6), (4, 5), (4, 6), (4, 6), (4, 8),
+    (4, 5), (4, 6), (4, 6), (4, 8), (4, 6), (4, 8), (4, 8), (0, 0),
+    (4, 4), (4, 6), (4, 6), (4, 8), (4, 6), (4, 8), (4, 8), (3, 8),
+    (4, 6), (4, 8), (4, 8), (3, 8), (4, 8), (3, 8), (1, 1), (0, 0),
+    (4, 4), (4, 5), (4, 5), (4, 8), (4, 5), (4, 8), (4, 8), (3, 8),
+    (4, 5), (4, 8), (4, 8), (3, 8), (4, 8), (3, 8), (3, 8), (0, 0),
+    (4, 4), (4, 8), (4, 8), (3, 8), (4, 8), (3, 8), (3, 8), (2, 8),
+    (3, 3), (2, 3), (2, 3), (1, 3), (2, 2), (1, 2), (1, 1), (0, 0),
+    (4, 4), (4, 5), (4, 5), (4, 6), (4, 5), (4, 6), (4, 6), (4, 7),
+    (4, 5), (4, 6), (4, 6), (4, 7), (4, 6), (4, 7), (4, 7), (0, 0),
+    (4, 4), (4, 6), (4, 6), (4, 7), (4, 6), (4, 7), (4, 7), (3, 7),
+    (4, 6), (4, 7), (4, 7), (3, 7), (4, 7), (3, 7), (1, 1), (0, 0),
+    (4, 4), (4, 5), (4, 5), (4, 7), (4, 5), (4, 7), (4, 7), (3, 7),
+    (4, 5), (4, 7), (4, 7), (3, 7), (4, 7), (3, 7), (3, 7), (0, 0),
+    (4, 4), (4, 7), (4, 7), (3, 7), (4, 7), (3, 7), (3, 7), (2, 7),
+    (4, 7), (3, 7), (3, 7), (2, 7), (2, 2), (1, 2), (1, 1), (0, 0),
+    (4, 4), (4, 5), (4, 5), (4, 6), (4, 5), (4, 6), (4, 6), (3, 6),
+    (4, 5), (4, 6), (4, 6), (3, 6), (4, 6), (3, 6), (3, 6), (0, 0),
+    (4, 4), (4, 6), (4, 6), (3, 6), (4, 6), (3, 6), (3, 6), (2, 6),
+    (4, 6), (3, 6), (3, 6), (2, 6), (3, 6), (2, 6), (1, 1), (0, 0),
+    (4, 4), (4, 5), (4, 5), (3, 5), (4, 5), (3, 5), (3, 5), (2, 5),
+    (4, 5), (3, 5), (3, 5), (2, 5), (3, 5), (2, 5), (2, 5), (0, 0),
+    (4, 4), (3, 4), (3, 4), (2, 4), (3, 4), (2, 4), (2, 4), (1, 4),
+    (3, 3), (2, 3), (2, 3), (1, 3), (2, 2), (1, 2), (1, 1), (0, 0),
+];
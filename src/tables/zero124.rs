@@ -0,0 +1,354 @@
+// Tables for the SIMD decoder of the 0124 variant, see `crate::zero124`. The
+// layout matches `DECODE_SHUFFLE_TABLE` and `LENGTH_TABLE`, but the 2-bit codes
+// mean 0, 1, 2 or 4 data bytes.
+//
+// Example:
+//
+// control byte: 00|01|11|00  =>  sizes: 0|1|4|0
+//
+// shuffle mask: [z, z, z, z, 0, 1, 2, 3, 4, z, z, z, z, z, z, z]
+// length: 5 bytes
+#[cfg(test)]
+fn zero124_step(control: u8) -> ([u8; 16], u8) {
+    let mut shuf = [0xff_u8; 16];
+    let mut src_ofs = 0;
+    for i in 0..4 {
+        let size = [0, 1, 2, 4][((control >> (2 * i)) & 0x3) as usize];
+        for j in 0..size {
+            shuf[4 * i + j] = src_ofs;
+            src_ofs += 1;
+        }
+    }
+    (shuf, src_ofs)
+}
+
+#[cfg(test)]
+#[test]
+fn build_zero124_tables() {
+    println!("#[rustfmt::skip]");
+    println!("pub static ZERO124_SHUFFLE_TABLE: [[u8; 16]; 256] = [");
+    for control in 0..=255u8 {
+        let (shuf, _) = zero124_step(control);
+        print!("    [");
+        for b in shuf {
+            if b < 0x80 {
+                print!("{:4}, ", b);
+            } else {
+                print!("0xff, ");
+            }
+        }
+        println!(
+            "],  // {}{}{}{}",
+            control >> 6,
+            (control >> 4) & 0x3,
+            (control >> 2) & 0x3,
+            control & 0x3
+        );
+    }
+    println!("];");
+    println!();
+    println!("#[rustfmt::skip]");
+    println!("pub static ZERO124_LENGTH_TABLE: [u8; 256] = [");
+    for row in 0..16u8 {
+        print!("    ");
+        for col in 0..16u8 {
+            let (_, len) = zero124_step(16 * row + col);
+            print!("{:2}, ", len);
+        }
+        println!();
+    }
+    println!("];")
+}
+
+#[cfg(test)]
+#[test]
+fn zero124_tables_match() {
+    for control in 0..=255u8 {
+        let (shuf, len) = zero124_step(control);
+        assert_eq!(ZERO124_SHUFFLE_TABLE[control as usize], shuf);
+        assert_eq!(ZERO124_LENGTH_TABLE[control as usize], len);
+    }
+}
+
+// generated using code above
+#[allow(unused)]
+#[rustfmt::skip]
+pub static ZERO124_SHUFFLE_TABLE: [[u8; 16]; 256] = [
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0000
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0001
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0002
+    [   0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0003
+    [0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0010
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0011
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0012
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0013
+    [0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0020
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0021
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0022
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0023
+    [0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0030
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0031
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0032
+    [   0,    1,    2,    3,    4,    5,    6,    7, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0033
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0100
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0101
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0102
+    [   0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0103
+    [0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0110
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0111
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0112
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0113
+    [0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0120
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0121
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0122
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff,    6, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0123
+    [0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3,    4, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0130
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0131
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5,    6, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0132
+    [   0,    1,    2,    3,    4,    5,    6,    7,    8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0133
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0200
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0201
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0202
+    [   0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0203
+    [0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0210
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0211
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0212
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5,    6, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0213
+    [0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0220
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0221
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0222
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff,    6,    7, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0223
+    [0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3,    4,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0230
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5,    6, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0231
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5,    6,    7, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0232
+    [   0,    1,    2,    3,    4,    5,    6,    7,    8,    9, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, ],  // 0233
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff, ],  // 0300
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    1,    2,    3,    4, 0xff, 0xff, 0xff, 0xff, ],  // 0301
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2,    3,    4,    5, 0xff, 0xff, 0xff, 0xff, ],  // 0302
+    [   0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff,    4,    5,    6,    7, 0xff, 0xff, 0xff, 0xff, ],  // 0303
+    [0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff,    1,    2,    3,    4, 0xff, 0xff, 0xff, 0xff, ],  // 0310
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3,    4,    5, 0xff, 0xff, 0xff, 0xff, ],  // 0311
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4,    5,    6, 0xff, 0xff, 0xff, 0xff, ],  // 0312
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5,    6,    7,    8, 0xff, 0xff, 0xff, 0xff, ],  // 0313
+    [0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff,    2,    3,    4,    5, 0xff, 0xff, 0xff, 0xff, ],  // 0320
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4,    5,    6, 0xff, 0xff, 0xff, 0xff, ],  // 0321
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5,    6,    7, 0xff, 0xff, 0xff, 0xff, ],  // 0322
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff,    6,    7,    8,    9, 0xff, 0xff, 0xff, 0xff, ],  // 0323
+    [0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3,    4,    5,    6,    7, 0xff, 0xff, 0xff, 0xff, ],  // 0330
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5,    6,    7,    8, 0xff, 0xff, 0xff, 0xff, ],  // 0331
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5,    6,    7,    8,    9, 0xff, 0xff, 0xff, 0xff, ],  // 0332
+    [   0,    1,    2,    3,    4,    5,    6,    7,    8,    9,   10,   11, 0xff, 0xff, 0xff, 0xff, ],  // 0333
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff, ],  // 1000
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff, ],  // 1001
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff, ],  // 1002
+    [   0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 1003
+    [0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff, ],  // 1010
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff, ],  // 1011
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 1012
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 1013
+    [0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff, ],  // 1020
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 1021
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 1022
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 1023
+    [0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 1030
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4, 0xff, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 1031
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5, 0xff, 0xff, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 1032
+    [   0,    1,    2,    3,    4,    5,    6,    7, 0xff, 0xff, 0xff, 0xff,    8, 0xff, 0xff, 0xff, ],  // 1033
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff, ],  // 1100
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff, ],  // 1101
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 1102
+    [   0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 1103
+    [0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff, ],  // 1110
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 1111
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 1112
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 1113
+    [0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 1120
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 1121
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 1122
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff,    6, 0xff, 0xff, 0xff,    7, 0xff, 0xff, 0xff, ],  // 1123
+    [0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 1130
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5, 0xff, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 1131
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5,    6, 0xff, 0xff, 0xff,    7, 0xff, 0xff, 0xff, ],  // 1132
+    [   0,    1,    2,    3,    4,    5,    6,    7,    8, 0xff, 0xff, 0xff,    9, 0xff, 0xff, 0xff, ],  // 1133
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff, ],  // 1200
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 1201
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 1202
+    [   0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 1203
+    [0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff, ],  // 1210
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 1211
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 1212
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5,    6, 0xff, 0xff,    7, 0xff, 0xff, 0xff, ],  // 1213
+    [0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff, ],  // 1220
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4, 0xff, 0xff,    5, 0xff, 0xff, 0xff, ],  // 1221
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 1222
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff,    6,    7, 0xff, 0xff,    8, 0xff, 0xff, 0xff, ],  // 1223
+    [0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3,    4,    5, 0xff, 0xff,    6, 0xff, 0xff, 0xff, ],  // 1230
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5,    6, 0xff, 0xff,    7, 0xff, 0xff, 0xff, ],  // 1231
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5,    6,    7, 0xff, 0xff,    8, 0xff, 0xff, 0xff, ],  // 1232
+    [   0,    1,    2,    3,    4,    5,    6,    7,    8,    9, 0xff, 0xff,   10, 0xff, 0xff, 0xff, ],  // 1233
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3,    4, 0xff, 0xff, 0xff, ],  // 1300
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5, 0xff, 0xff, 0xff, ],  // 1301
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2,    3,    4,    5,    6, 0xff, 0xff, 0xff, ],  // 1302
+    [   0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff,    4,    5,    6,    7,    8, 0xff, 0xff, 0xff, ],  // 1303
+    [0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5, 0xff, 0xff, 0xff, ],  // 1310
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3,    4,    5,    6, 0xff, 0xff, 0xff, ],  // 1311
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4,    5,    6,    7, 0xff, 0xff, 0xff, ],  // 1312
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5,    6,    7,    8,    9, 0xff, 0xff, 0xff, ],  // 1313
+    [0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff,    2,    3,    4,    5,    6, 0xff, 0xff, 0xff, ],  // 1320
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4,    5,    6,    7, 0xff, 0xff, 0xff, ],  // 1321
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5,    6,    7,    8, 0xff, 0xff, 0xff, ],  // 1322
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff,    6,    7,    8,    9,   10, 0xff, 0xff, 0xff, ],  // 1323
+    [0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3,    4,    5,    6,    7,    8, 0xff, 0xff, 0xff, ],  // 1330
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5,    6,    7,    8,    9, 0xff, 0xff, 0xff, ],  // 1331
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5,    6,    7,    8,    9,   10, 0xff, 0xff, 0xff, ],  // 1332
+    [   0,    1,    2,    3,    4,    5,    6,    7,    8,    9,   10,   11,   12, 0xff, 0xff, 0xff, ],  // 1333
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff, ],  // 2000
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff, ],  // 2001
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff, ],  // 2002
+    [   0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 2003
+    [0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff, ],  // 2010
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff, ],  // 2011
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff, ],  // 2012
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    5,    6, 0xff, 0xff, ],  // 2013
+    [0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff, ],  // 2020
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff, ],  // 2021
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 2022
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    6,    7, 0xff, 0xff, ],  // 2023
+    [0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 2030
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4, 0xff, 0xff, 0xff, 0xff,    5,    6, 0xff, 0xff, ],  // 2031
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5, 0xff, 0xff, 0xff, 0xff,    6,    7, 0xff, 0xff, ],  // 2032
+    [   0,    1,    2,    3,    4,    5,    6,    7, 0xff, 0xff, 0xff, 0xff,    8,    9, 0xff, 0xff, ],  // 2033
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff, ],  // 2100
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff, ],  // 2101
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff, ],  // 2102
+    [   0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5,    6, 0xff, 0xff, ],  // 2103
+    [0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff, ],  // 2110
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff, ],  // 2111
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 2112
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff,    6,    7, 0xff, 0xff, ],  // 2113
+    [0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff, ],  // 2120
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 2121
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5,    6, 0xff, 0xff, ],  // 2122
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff,    6, 0xff, 0xff, 0xff,    7,    8, 0xff, 0xff, ],  // 2123
+    [0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5,    6, 0xff, 0xff, ],  // 2130
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5, 0xff, 0xff, 0xff,    6,    7, 0xff, 0xff, ],  // 2131
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5,    6, 0xff, 0xff, 0xff,    7,    8, 0xff, 0xff, ],  // 2132
+    [   0,    1,    2,    3,    4,    5,    6,    7,    8, 0xff, 0xff, 0xff,    9,   10, 0xff, 0xff, ],  // 2133
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff, ],  // 2200
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4, 0xff, 0xff, ],  // 2201
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 2202
+    [   0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff,    6,    7, 0xff, 0xff, ],  // 2203
+    [0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4, 0xff, 0xff, ],  // 2210
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 2211
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff,    5,    6, 0xff, 0xff, ],  // 2212
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5,    6, 0xff, 0xff,    7,    8, 0xff, 0xff, ],  // 2213
+    [0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5, 0xff, 0xff, ],  // 2220
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4, 0xff, 0xff,    5,    6, 0xff, 0xff, ],  // 2221
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5, 0xff, 0xff,    6,    7, 0xff, 0xff, ],  // 2222
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff,    6,    7, 0xff, 0xff,    8,    9, 0xff, 0xff, ],  // 2223
+    [0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3,    4,    5, 0xff, 0xff,    6,    7, 0xff, 0xff, ],  // 2230
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5,    6, 0xff, 0xff,    7,    8, 0xff, 0xff, ],  // 2231
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5,    6,    7, 0xff, 0xff,    8,    9, 0xff, 0xff, ],  // 2232
+    [   0,    1,    2,    3,    4,    5,    6,    7,    8,    9, 0xff, 0xff,   10,   11, 0xff, 0xff, ],  // 2233
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3,    4,    5, 0xff, 0xff, ],  // 2300
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5,    6, 0xff, 0xff, ],  // 2301
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2,    3,    4,    5,    6,    7, 0xff, 0xff, ],  // 2302
+    [   0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff,    4,    5,    6,    7,    8,    9, 0xff, 0xff, ],  // 2303
+    [0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5,    6, 0xff, 0xff, ],  // 2310
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3,    4,    5,    6,    7, 0xff, 0xff, ],  // 2311
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4,    5,    6,    7,    8, 0xff, 0xff, ],  // 2312
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5,    6,    7,    8,    9,   10, 0xff, 0xff, ],  // 2313
+    [0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff,    2,    3,    4,    5,    6,    7, 0xff, 0xff, ],  // 2320
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4,    5,    6,    7,    8, 0xff, 0xff, ],  // 2321
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5,    6,    7,    8,    9, 0xff, 0xff, ],  // 2322
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff,    6,    7,    8,    9,   10,   11, 0xff, 0xff, ],  // 2323
+    [0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3,    4,    5,    6,    7,    8,    9, 0xff, 0xff, ],  // 2330
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5,    6,    7,    8,    9,   10, 0xff, 0xff, ],  // 2331
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5,    6,    7,    8,    9,   10,   11, 0xff, 0xff, ],  // 2332
+    [   0,    1,    2,    3,    4,    5,    6,    7,    8,    9,   10,   11,   12,   13, 0xff, 0xff, ],  // 2333
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3, ],  // 3000
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    1,    2,    3,    4, ],  // 3001
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2,    3,    4,    5, ],  // 3002
+    [   0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    4,    5,    6,    7, ],  // 3003
+    [0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    1,    2,    3,    4, ],  // 3010
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2,    3,    4,    5, ],  // 3011
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    3,    4,    5,    6, ],  // 3012
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    5,    6,    7,    8, ],  // 3013
+    [0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2,    3,    4,    5, ],  // 3020
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    3,    4,    5,    6, ],  // 3021
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    4,    5,    6,    7, ],  // 3022
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    6,    7,    8,    9, ],  // 3023
+    [0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff,    4,    5,    6,    7, ],  // 3030
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4, 0xff, 0xff, 0xff, 0xff,    5,    6,    7,    8, ],  // 3031
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5, 0xff, 0xff, 0xff, 0xff,    6,    7,    8,    9, ],  // 3032
+    [   0,    1,    2,    3,    4,    5,    6,    7, 0xff, 0xff, 0xff, 0xff,    8,    9,   10,   11, ],  // 3033
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff,    1,    2,    3,    4, ],  // 3100
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3,    4,    5, ],  // 3101
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4,    5,    6, ],  // 3102
+    [   0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5,    6,    7,    8, ],  // 3103
+    [0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3,    4,    5, ],  // 3110
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4,    5,    6, ],  // 3111
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4,    5,    6,    7, ],  // 3112
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5, 0xff, 0xff, 0xff,    6,    7,    8,    9, ],  // 3113
+    [0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4,    5,    6, ],  // 3120
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3, 0xff, 0xff, 0xff,    4,    5,    6,    7, ],  // 3121
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4, 0xff, 0xff, 0xff,    5,    6,    7,    8, ],  // 3122
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff,    6, 0xff, 0xff, 0xff,    7,    8,    9,   10, ],  // 3123
+    [0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5,    6,    7,    8, ],  // 3130
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5, 0xff, 0xff, 0xff,    6,    7,    8,    9, ],  // 3131
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5,    6, 0xff, 0xff, 0xff,    7,    8,    9,   10, ],  // 3132
+    [   0,    1,    2,    3,    4,    5,    6,    7,    8, 0xff, 0xff, 0xff,    9,   10,   11,   12, ],  // 3133
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff,    2,    3,    4,    5, ],  // 3200
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4,    5,    6, ],  // 3201
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5,    6,    7, ],  // 3202
+    [   0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff,    4,    5, 0xff, 0xff,    6,    7,    8,    9, ],  // 3203
+    [0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4,    5,    6, ],  // 3210
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5,    6,    7, ],  // 3211
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4, 0xff, 0xff,    5,    6,    7,    8, ],  // 3212
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5,    6, 0xff, 0xff,    7,    8,    9,   10, ],  // 3213
+    [0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5,    6,    7, ],  // 3220
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4, 0xff, 0xff,    5,    6,    7,    8, ],  // 3221
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5, 0xff, 0xff,    6,    7,    8,    9, ],  // 3222
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff,    6,    7, 0xff, 0xff,    8,    9,   10,   11, ],  // 3223
+    [0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3,    4,    5, 0xff, 0xff,    6,    7,    8,    9, ],  // 3230
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5,    6, 0xff, 0xff,    7,    8,    9,   10, ],  // 3231
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5,    6,    7, 0xff, 0xff,    8,    9,   10,   11, ],  // 3232
+    [   0,    1,    2,    3,    4,    5,    6,    7,    8,    9, 0xff, 0xff,   10,   11,   12,   13, ],  // 3233
+    [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3,    4,    5,    6,    7, ],  // 3300
+    [   0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5,    6,    7,    8, ],  // 3301
+    [   0,    1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,    2,    3,    4,    5,    6,    7,    8,    9, ],  // 3302
+    [   0,    1,    2,    3, 0xff, 0xff, 0xff, 0xff,    4,    5,    6,    7,    8,    9,   10,   11, ],  // 3303
+    [0xff, 0xff, 0xff, 0xff,    0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5,    6,    7,    8, ],  // 3310
+    [   0, 0xff, 0xff, 0xff,    1, 0xff, 0xff, 0xff,    2,    3,    4,    5,    6,    7,    8,    9, ],  // 3311
+    [   0,    1, 0xff, 0xff,    2, 0xff, 0xff, 0xff,    3,    4,    5,    6,    7,    8,    9,   10, ],  // 3312
+    [   0,    1,    2,    3,    4, 0xff, 0xff, 0xff,    5,    6,    7,    8,    9,   10,   11,   12, ],  // 3313
+    [0xff, 0xff, 0xff, 0xff,    0,    1, 0xff, 0xff,    2,    3,    4,    5,    6,    7,    8,    9, ],  // 3320
+    [   0, 0xff, 0xff, 0xff,    1,    2, 0xff, 0xff,    3,    4,    5,    6,    7,    8,    9,   10, ],  // 3321
+    [   0,    1, 0xff, 0xff,    2,    3, 0xff, 0xff,    4,    5,    6,    7,    8,    9,   10,   11, ],  // 3322
+    [   0,    1,    2,    3,    4,    5, 0xff, 0xff,    6,    7,    8,    9,   10,   11,   12,   13, ],  // 3323
+    [0xff, 0xff, 0xff, 0xff,    0,    1,    2,    3,    4,    5,    6,    7,    8,    9,   10,   11, ],  // 3330
+    [   0, 0xff, 0xff, 0xff,    1,    2,    3,    4,    5,    6,    7,    8,    9,   10,   11,   12, ],  // 3331
+    [   0,    1, 0xff, 0xff,    2,    3,    4,    5,    6,    7,    8,    9,   10,   11,   12,   13, ],  // 3332
+    [   0,    1,    2,    3,    4,    5,    6,    7,    8,    9,   10,   11,   12,   13,   14,   15, ],  // 3333
+];
+
+#[allow(unused)]
+#[rustfmt::skip]
+pub static ZERO124_LENGTH_TABLE: [u8; 256] = [
+     0,  1,  2,  4,  1,  2,  3,  5,  2,  3,  4,  6,  4,  5,  6,  8, 
+     1,  2,  3,  5,  2,  3,  4,  6,  3,  4,  5,  7,  5,  6,  7,  9, 
+     2,  3,  4,  6,  3,  4,  5,  7,  4,  5,  6,  8,  6,  7,  8, 10, 
+     4,  5,  6,  8,  5,  6,  7,  9,  6,  7,  8, 10,  8,  9, 10, 12, 
+     1,  2,  3,  5,  2,  3,  4,  6,  3,  4,  5,  7,  5,  6,  7,  9, 
+     2,  3,  4,  6,  3,  4,  5,  7,  4,  5,  6,  8,  6,  7,  8, 10, 
+     3,  4,  5,  7,  4,  5,  6,  8,  5,  6,  7,  9,  7,  8,  9, 11, 
+     5,  6,  7,  9,  6,  7,  8, 10,  7,  8,  9, 11,  9, 10, 11, 13, 
+     2,  3,  4,  6,  3,  4,  5,  7,  4,  5,  6,  8,  6,  7,  8, 10, 
+     3,  4,  5,  7,  4,  5,  6,  8,  5,  6,  7,  9,  7,  8,  9, 11, 
+     4,  5,  6,  8,  5,  6,  7,  9,  6,  7,  8, 10,  8,  9, 10, 12, 
+     6,  7,  8, 10,  7,  8,  9, 11,  8,  9, 10, 12, 10, 11, 12, 14, 
+     4,  5,  6,  8,  5,  6,  7,  9,  6,  7,  8, 10,  8,  9, 10, 12, 
+     5,  6,  7,  9,  6,  7,  8, 10,  7,  8,  9, 11,  9, 10, 11, 13, 
+     6,  7,  8, 10,  7,  8,  9, 11,  8,  9, 10, 12, 10, 11, 12, 14, 
+     8,  9, 10, 12,  9, 10, 11, 13, 10, 11, 12, 14, 12, 13, 14, 16, 
+];
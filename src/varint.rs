@@ -0,0 +1,317 @@
+//! LEB128 varints, as used by Protocol Buffers.
+//!
+//! Every byte holds 7 bits of the value, least significant group first. The
+//! high bit of a byte is set if more bytes follow. Unlike StreamVByte, the
+//! length information is interleaved with the data, so the format is
+//! self-delimiting but harder to decode quickly.
+//!
+//! Decoding `u32` values uses SSSE3 if the crate is compiled with it.
+//! [to_streamvbyte] and [from_streamvbyte] convert between the two formats.
+//!
+//! Decoding is strict: a varint whose value does not fit into the requested
+//! type is rejected with [StreamVbyteError::InvalidVarint]. Protocol Buffers
+//! writes negative `int32` values as 10-byte varints, decode those with
+//! [decode_u64].
+use crate::common::{zigzag_encode_1, StreamVbyteError};
+
+/// Encode a slice of `u32` values as varints.
+///
+/// ```
+/// let (len, bytes) = streamvb::varint::encode(&[1, 300, 70000]);
+/// assert_eq!(bytes, vec![1, 0xac, 0x02, 0xf0, 0xa2, 0x04]);
+/// assert_eq!(streamvb::varint::decode(len, &bytes).unwrap(), vec![1, 300, 70000]);
+/// ```
+pub fn encode(values: &[u32]) -> (usize, Vec<u8>) {
+    let mut output = Vec::with_capacity(values.len());
+    for &value in values {
+        write_varint(&mut output, value as u64);
+    }
+    (values.len(), output)
+}
+
+/// Decode `len` varints into `u32` values.
+///
+/// Bytes after the `len`-th varint are ignored. Returns an error if the input
+/// ends early or a value does not fit into a `u32`.
+#[allow(clippy::needless_return)]
+pub fn decode(len: usize, input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    ))]
+    {
+        return crate::x86_64::varint::decode_simd::<crate::x86_64::decode::NoDecode>(len, input);
+    }
+
+    #[cfg(not(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    )))]
+    {
+        decode_scalar(len, input, |x| x)
+    }
+}
+
+/// Encode a slice of `i32` values, stored as `u32`, as zigzag varints (the
+/// `sint32` type of Protocol Buffers).
+///
+/// ```
+/// let values = [0, -1i32 as u32, 1, -2i32 as u32];
+/// let (len, bytes) = streamvb::varint::encode_zigzag(&values);
+/// assert_eq!(bytes, vec![0, 1, 2, 3]);
+/// assert_eq!(streamvb::varint::decode_zigzag(len, &bytes).unwrap(), values);
+/// ```
+pub fn encode_zigzag(values: &[u32]) -> (usize, Vec<u8>) {
+    let mut output = Vec::with_capacity(values.len());
+    for &value in values {
+        write_varint(&mut output, zigzag_encode_1(value) as u64);
+    }
+    (values.len(), output)
+}
+
+/// Decode `len` zigzag varints written by [encode_zigzag].
+#[allow(clippy::needless_return)]
+pub fn decode_zigzag(len: usize, input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    ))]
+    {
+        return crate::x86_64::varint::decode_simd::<crate::x86_64::decode::ZigZagDecode>(
+            len, input,
+        );
+    }
+
+    #[cfg(not(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    )))]
+    {
+        decode_scalar(len, input, crate::common::zigzag_decode_1)
+    }
+}
+
+/// Encode a slice of `u64` values as varints.
+///
+/// ```
+/// let values = [1, u64::MAX];
+/// let (len, bytes) = streamvb::varint::encode_u64(&values);
+/// assert_eq!(bytes.len(), 11);
+/// assert_eq!(streamvb::varint::decode_u64(len, &bytes).unwrap(), values);
+/// ```
+pub fn encode_u64(values: &[u64]) -> (usize, Vec<u8>) {
+    let mut output = Vec::with_capacity(values.len());
+    for &value in values {
+        write_varint(&mut output, value);
+    }
+    (values.len(), output)
+}
+
+/// Decode `len` varints into `u64` values.
+pub fn decode_u64(len: usize, input: &[u8]) -> Result<Vec<u64>, StreamVbyteError> {
+    decode_u64_with(len, input, |x| x)
+}
+
+/// Encode a slice of `i64` values, stored as `u64`, as zigzag varints (the
+/// `sint64` type of Protocol Buffers).
+pub fn encode_zigzag_u64(values: &[u64]) -> (usize, Vec<u8>) {
+    let mut output = Vec::with_capacity(values.len());
+    for &value in values {
+        let value = value as i64;
+        write_varint(&mut output, ((value << 1) ^ (value >> 63)) as u64);
+    }
+    (values.len(), output)
+}
+
+/// Decode `len` zigzag varints written by [encode_zigzag_u64].
+pub fn decode_zigzag_u64(len: usize, input: &[u8]) -> Result<Vec<u64>, StreamVbyteError> {
+    decode_u64_with(len, input, |x| (x >> 1) ^ 0u64.wrapping_sub(x & 1))
+}
+
+/// Transcode `len` varints into the StreamVByte format of [crate::encode].
+///
+/// ```
+/// let (len, varints) = streamvb::varint::encode(&[1, 300, 70000]);
+/// let bytes = streamvb::varint::to_streamvbyte(len, &varints).unwrap();
+/// assert_eq!(bytes, streamvb::encode(&[1, 300, 70000]).1);
+/// ```
+pub fn to_streamvbyte(len: usize, input: &[u8]) -> Result<Vec<u8>, StreamVbyteError> {
+    let values = decode(len, input)?;
+    let mut output = Vec::new();
+    crate::encode_into(&values, &mut output);
+    Ok(output)
+}
+
+/// Transcode `len` values in the StreamVByte format of [crate::encode] into
+/// varints.
+///
+/// ```
+/// let (len, bytes) = streamvb::encode(&[1, 300, 70000]);
+/// let varints = streamvb::varint::from_streamvbyte(len, &bytes).unwrap();
+/// assert_eq!(varints, streamvb::varint::encode(&[1, 300, 70000]).1);
+/// ```
+pub fn from_streamvbyte(len: usize, input: &[u8]) -> Result<Vec<u8>, StreamVbyteError> {
+    let values = crate::decode(len, input)?;
+    Ok(encode(&values).1)
+}
+
+fn write_varint(output: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        output.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    output.push(value as u8);
+}
+
+/// Read the varint at `input[*offset..]`, which must fit into `bits` bits,
+/// and advance `offset` past it.
+#[inline]
+pub(crate) fn read_varint(
+    input: &[u8],
+    offset: &mut usize,
+    bits: u32,
+) -> Result<u64, StreamVbyteError> {
+    let mut result = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = *input
+            .get(*offset)
+            .ok_or(StreamVbyteError::DecodeOutOfBounds)?;
+        *offset += 1;
+        let group = (byte & 0x7f) as u64;
+        if shift >= bits || (bits - shift < 7 && group >> (bits - shift) != 0) {
+            return Err(StreamVbyteError::InvalidVarint);
+        }
+        result |= group << shift;
+        if byte < 0x80 {
+            return Ok(result);
+        }
+        shift += 7;
+    }
+}
+
+#[cfg_attr(
+    all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    ),
+    allow(dead_code)
+)]
+fn decode_scalar<F>(len: usize, input: &[u8], f: F) -> Result<Vec<u32>, StreamVbyteError>
+where
+    F: Fn(u32) -> u32,
+{
+    // Every varint takes at least one byte. Checked before allocating, so a
+    // bogus `len` cannot trigger a huge allocation.
+    if len > input.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    let mut output = Vec::with_capacity(len);
+    let mut offset = 0;
+    for _ in 0..len {
+        output.push(f(read_varint(input, &mut offset, 32)? as u32));
+    }
+    Ok(output)
+}
+
+fn decode_u64_with<F>(len: usize, input: &[u8], f: F) -> Result<Vec<u64>, StreamVbyteError>
+where
+    F: Fn(u64) -> u64,
+{
+    if len > input.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    let mut output = Vec::with_capacity(len);
+    let mut offset = 0;
+    for _ in 0..len {
+        output.push(f(read_varint(input, &mut offset, 64)?));
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_inputs() -> Vec<Vec<u32>> {
+        crate::common::sample_inputs(&[
+            127,
+            128,
+            16383,
+            16384,
+            (1 << 21) - 1,
+            1 << 21,
+            (1 << 28) - 1,
+            1 << 28,
+            u32::MAX,
+        ])
+    }
+
+    #[test]
+    fn encode_decode() {
+        for input in sample_inputs() {
+            let (len, bytes) = encode(&input);
+            assert_eq!(decode(len, &bytes).unwrap(), input);
+            assert_eq!(decode_scalar(len, &bytes, |x| x).unwrap(), input);
+            let wide: Vec<u64> = input.iter().map(|&x| x as u64).collect();
+            assert_eq!(encode_u64(&wide).1, bytes);
+            assert_eq!(decode_u64(len, &bytes).unwrap(), wide);
+            for end in 0..bytes.len() {
+                assert!(decode(len, &bytes[..end]).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn zigzag() {
+        for input in sample_inputs() {
+            let (len, bytes) = encode_zigzag(&input);
+            assert_eq!(decode_zigzag(len, &bytes).unwrap(), input);
+            let zigzag: Vec<u32> = input.iter().map(|&x| zigzag_encode_1(x)).collect();
+            assert_eq!(encode(&zigzag).1, bytes);
+        }
+        let values = [0, 1, u64::MAX, i64::MIN as u64, i64::MAX as u64];
+        let (len, bytes) = encode_zigzag_u64(&values);
+        assert_eq!(&bytes[..3], &[0, 2, 1]);
+        assert_eq!(decode_zigzag_u64(len, &bytes).unwrap(), values);
+    }
+
+    #[test]
+    fn too_long() {
+        // u32::MAX + 1, and a negative protobuf `int32`.
+        let bytes = [0x80, 0x80, 0x80, 0x80, 0x10];
+        assert!(matches!(
+            decode(1, &bytes),
+            Err(StreamVbyteError::InvalidVarint)
+        ));
+        assert_eq!(decode_u64(1, &bytes).unwrap(), vec![1 << 32]);
+        let mut bytes = vec![0xff; 9];
+        bytes.push(0x01);
+        assert!(decode(1, &bytes).is_err());
+        assert_eq!(decode_u64(1, &bytes).unwrap(), vec![u64::MAX]);
+        bytes[9] = 0x03;
+        assert!(decode_u64(1, &bytes).is_err());
+        // Inside a block the SIMD decoder would otherwise handle.
+        let mut bytes = encode(&[1; 40]).1;
+        bytes.splice(20..20, [0x80, 0x80, 0x80, 0x80, 0x80, 0x01]);
+        assert!(matches!(
+            decode(41, &bytes),
+            Err(StreamVbyteError::InvalidVarint)
+        ));
+    }
+
+    #[test]
+    fn transcode() {
+        for input in sample_inputs() {
+            let (len, varints) = encode(&input);
+            let (_, bytes) = crate::encode(&input);
+            assert_eq!(to_streamvbyte(len, &varints).unwrap(), bytes);
+            assert_eq!(from_streamvbyte(len, &bytes).unwrap(), varints);
+        }
+    }
+}
@@ -1,37 +1,36 @@
-use std::arch::x86_64::{__m128i, _mm_loadu_si128, _mm_shuffle_epi8, _mm_storeu_si128};
+use crate::x86_64::arch::{__m128i, _mm_loadu_si128, _mm_shuffle_epi8, _mm_storeu_si128};
 
 use crate::{
-    common::{control_bytes_len, StreamVbyteError},
+    common::{
+        control_bytes_len, DeltaDecode, FrameOfReferenceDecode, ScalarDecoder, StreamVbyteError,
+    },
     tables::len::LENGTH_TABLE,
     tables::shuffle::DECODE_SHUFFLE_TABLE,
+    tables::zero124::{ZERO124_LENGTH_TABLE, ZERO124_SHUFFLE_TABLE},
 };
 
-pub(crate) trait Decoder {
+pub(crate) use crate::common::{NoDecode, ZigZagDecode};
+
+// Adds the vectorised version of a transform. `simd_decode_4x32` sees the
+// values in order, and `decode_1` the values after them.
+pub(crate) trait Decoder: ScalarDecoder {
     #[cfg(target_feature = "sse2")]
-    unsafe fn simd_decode_4x32(data: __m128i) -> __m128i;
-    fn decode_1(x: u32) -> u32;
+    unsafe fn simd_decode_4x32(&mut self, data: __m128i) -> __m128i;
 }
 
-pub(crate) struct NoDecode;
 impl Decoder for NoDecode {
     #[cfg(target_feature = "sse2")]
     #[inline]
-    unsafe fn simd_decode_4x32(data: __m128i) -> __m128i {
+    unsafe fn simd_decode_4x32(&mut self, data: __m128i) -> __m128i {
         data
     }
-
-    #[inline]
-    fn decode_1(x: u32) -> u32 {
-        x
-    }
 }
 
-pub(crate) struct ZigZagDecode;
 impl Decoder for ZigZagDecode {
     #[cfg(target_feature = "sse2")]
     #[inline]
-    unsafe fn simd_decode_4x32(data: __m128i) -> __m128i {
-        use std::arch::x86_64::{
+    unsafe fn simd_decode_4x32(&mut self, data: __m128i) -> __m128i {
+        use crate::x86_64::arch::{
             _mm_and_si128, _mm_set1_epi32, _mm_setzero_si128, _mm_srli_epi32, _mm_sub_epi32,
             _mm_xor_si128,
         };
@@ -43,14 +42,39 @@ impl Decoder for ZigZagDecode {
         let mask = _mm_sub_epi32(zero, zero_or_one);
         _mm_xor_si128(data_shr_1, mask)
     }
+}
 
+impl<D: Decoder> Decoder for DeltaDecode<D> {
+    #[cfg(target_feature = "sse2")]
     #[inline]
-    fn decode_1(x: u32) -> u32 {
-        (x >> 1) ^ (0u32.wrapping_sub(x & 1))
+    unsafe fn simd_decode_4x32(&mut self, data: __m128i) -> __m128i {
+        use crate::x86_64::arch::{
+            _mm_add_epi32, _mm_cvtsi128_si32, _mm_set1_epi32, _mm_shuffle_epi32, _mm_slli_si128,
+        };
+
+        let data = self.inner.simd_decode_4x32(data);
+        // Prefix sum within the register: add the values shifted up by one,
+        // then by two lanes.
+        let data = _mm_add_epi32(data, _mm_slli_si128::<4>(data));
+        let data = _mm_add_epi32(data, _mm_slli_si128::<8>(data));
+        let data = _mm_add_epi32(data, _mm_set1_epi32(self.prev as i32));
+        self.prev = _mm_cvtsi128_si32(_mm_shuffle_epi32::<0xff>(data)) as u32;
+        data
     }
 }
 
-pub(crate) fn decode_simd<D: Decoder>(
+impl Decoder for FrameOfReferenceDecode {
+    #[cfg(target_feature = "sse2")]
+    #[inline]
+    unsafe fn simd_decode_4x32(&mut self, data: __m128i) -> __m128i {
+        use crate::x86_64::arch::{_mm_add_epi32, _mm_set1_epi32};
+
+        _mm_add_epi32(data, _mm_set1_epi32(self.reference as i32))
+    }
+}
+
+#[inline]
+pub(crate) fn decode_simd<D: Decoder + Default>(
     len: usize,
     input: &[u8],
 ) -> Result<Vec<u32>, StreamVbyteError> {
@@ -59,7 +83,8 @@ pub(crate) fn decode_simd<D: Decoder>(
     Ok(output)
 }
 
-pub(crate) fn decode_into_simd<D: Decoder>(
+#[inline]
+pub(crate) fn decode_into_simd<D: Decoder + Default>(
     len: usize,
     input: &[u8],
     output: &mut Vec<u32>,
@@ -67,17 +92,86 @@ pub(crate) fn decode_into_simd<D: Decoder>(
     if len == 0 {
         return Ok(());
     }
+    let num_controls = control_bytes_len(len);
+    if num_controls >= input.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    let (control, data) = input.split_at(num_controls);
+    decode_parts_into_simd::<D>(len, control, data, output)
+}
+
+/// Decode `len` values from separate control and data streams and append them
+/// to `output`. `data` may extend past the bytes used by the `len` values.
+#[inline]
+pub(crate) fn decode_parts_into_simd<D: Decoder + Default>(
+    len: usize,
+    control: &[u8],
+    data: &[u8],
+    output: &mut Vec<u32>,
+) -> Result<(), StreamVbyteError> {
+    decode_parts_into_with(&mut D::default(), len, control, data, output)
+}
+
+/// Like [decode_parts_into_simd], but transforms the values with `decoder`.
+#[inline]
+pub(crate) fn decode_parts_into_with<D: Decoder>(
+    decoder: &mut D,
+    len: usize,
+    control: &[u8],
+    data: &[u8],
+    output: &mut Vec<u32>,
+) -> Result<(), StreamVbyteError> {
+    if len == 0 {
+        return Ok(());
+    }
+    // Checked before reserving, so a bogus `len` cannot trigger a huge
+    // allocation.
+    if control.len() < control_bytes_len(len) {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
     output.reserve(len);
-    let mut output_ptr: *mut u32 = unsafe { output.as_mut_ptr().add(output.len()) };
+    unsafe {
+        let output_ptr: *mut u32 = output.as_mut_ptr().add(output.len());
+        decode_parts_to_ptr_with(decoder, len, control, data, output_ptr)?;
+        output.set_len(output.len() + len);
+    }
+    Ok(())
+}
+
+/// Like [decode_parts_into_simd], but writes exactly `len` values to
+/// `output_ptr`.
+///
+/// Safety: `output_ptr` must be valid for writing `len` values.
+#[cfg(feature = "rayon")]
+#[inline]
+pub(crate) unsafe fn decode_parts_to_ptr_simd<D: Decoder + Default>(
+    len: usize,
+    control: &[u8],
+    data: &[u8],
+    output_ptr: *mut u32,
+) -> Result<(), StreamVbyteError> {
+    decode_parts_to_ptr_with(&mut D::default(), len, control, data, output_ptr)
+}
 
-    let end: *const u8 = input.as_ptr_range().end;
+#[inline]
+unsafe fn decode_parts_to_ptr_with<D: Decoder>(
+    decoder: &mut D,
+    len: usize,
+    control: &[u8],
+    data: &[u8],
+    mut output_ptr: *mut u32,
+) -> Result<(), StreamVbyteError> {
+    if len == 0 {
+        return Ok(());
+    }
     let num_controls = control_bytes_len(len);
-    if num_controls >= input.len() {
+    if control.len() < num_controls {
         return Err(StreamVbyteError::DecodeOutOfBounds);
     }
-    let mut control_ptr: *const u8 = input.as_ptr();
-    let mut data_ptr: *const u8 = unsafe { input.as_ptr().add(num_controls) };
-    //let mut result: Vec<u32> = Vec::with_capacity(len);
+
+    let end: *const u8 = data.as_ptr_range().end;
+    let mut control_ptr: *const u8 = control.as_ptr();
+    let mut data_ptr: *const u8 = data.as_ptr();
 
     let mut remaining_len = len;
 
@@ -86,102 +180,138 @@ pub(crate) fn decode_into_simd<D: Decoder>(
     // Therefore we need to read at least 4 control bytes. But the last byte
     // might be partial, so we need > 4 control bytes.
     if num_controls > 4 {
-        unsafe {
-            let num_controls = num_controls - 4;
-            let (new_data_ptr, ok) = decode_ssse3_worker_checked_unrolled::<D>(
-                control_ptr,
-                data_ptr,
-                end,
-                output_ptr,
-                num_controls,
-            );
-            if !ok {
-                return Err(StreamVbyteError::DecodeOutOfBounds);
-            }
-            data_ptr = new_data_ptr;
-            control_ptr = control_ptr.add(num_controls);
-            output_ptr = output_ptr.add(4 * num_controls);
-            remaining_len -= 4 * num_controls;
-        }
-    }
-    // Decode the leftovers using scalar decoder.
-    unsafe {
-        let (_, ok) = crate::scalar::decode::decode_unroll_inner_checked(
+        let (new_data_ptr, done) = decode_ssse3_worker_unrolled(
+            decoder,
             control_ptr,
             data_ptr,
             end,
             output_ptr,
-            remaining_len,
-            |x| D::decode_1(x),
+            num_controls - 4,
         );
-        if !ok {
-            return Err(StreamVbyteError::DecodeOutOfBounds);
-        }
+        data_ptr = new_data_ptr;
+        control_ptr = control_ptr.add(done);
+        output_ptr = output_ptr.add(4 * done);
+        remaining_len -= 4 * done;
+    }
+    // Decode the leftovers using scalar decoder.
+    let (_, ok) = crate::scalar::decode::decode_unroll_inner_checked(
+        control_ptr,
+        data_ptr,
+        end,
+        output_ptr,
+        remaining_len,
+        |x| decoder.decode_1(x),
+    );
+    if !ok {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
     }
-
-    unsafe { output.set_len(output.len() + len) };
-
     Ok(())
 }
 
-unsafe fn decode_ssse3_worker_checked_unrolled<D: Decoder>(
-    mut control_ptr: *const u8,
+// Decodes up to `num_controls` full control bytes, stopping early when fewer
+// than 16 data bytes are left to read. Returns the new data pointer and the
+// number of control bytes consumed; the caller decodes the rest.
+#[inline]
+unsafe fn decode_ssse3_worker_unrolled<D: Decoder>(
+    decoder: &mut D,
+    control_ptr: *const u8,
     mut data_ptr: *const u8,
     end_ptr: *const u8,
     mut decoded_ptr: *mut u32,
-    mut num_controls: usize,
-) -> (*const u8, bool) {
-    // println!(
-    //     "n={:?}, ctrl={:?}, data={:?}, end={:?}, out={:?}",
-    //     num_controls, control_ptr, data_ptr, end_ptr, decoded_ptr,
-    // );
-    while num_controls >= 4 {
-        let control1 = *control_ptr;
-        control_ptr = control_ptr.add(1);
-        let control2 = *control_ptr;
-        control_ptr = control_ptr.add(1);
-        let control3 = *control_ptr;
-        control_ptr = control_ptr.add(1);
-        let control4 = *control_ptr;
-        control_ptr = control_ptr.add(1);
-
-        if data_ptr.add(64) > end_ptr {
-            break;
-            // return (data_ptr, false);
-        }
-
-        num_controls -= 4;
-
-        data_ptr = step_simd::<D>(control1, data_ptr, decoded_ptr);
+    num_controls: usize,
+) -> (*const u8, usize) {
+    let mut done = 0;
+    while done + 4 <= num_controls && end_ptr.offset_from(data_ptr) >= 64 {
+        let control1 = *control_ptr.add(done);
+        let control2 = *control_ptr.add(done + 1);
+        let control3 = *control_ptr.add(done + 2);
+        let control4 = *control_ptr.add(done + 3);
+        done += 4;
+
+        data_ptr = step_simd(decoder, control1, data_ptr, decoded_ptr);
         decoded_ptr = decoded_ptr.add(4_usize);
-        data_ptr = step_simd::<D>(control2, data_ptr, decoded_ptr);
+        data_ptr = step_simd(decoder, control2, data_ptr, decoded_ptr);
         decoded_ptr = decoded_ptr.add(4_usize);
-        data_ptr = step_simd::<D>(control3, data_ptr, decoded_ptr);
+        data_ptr = step_simd(decoder, control3, data_ptr, decoded_ptr);
         decoded_ptr = decoded_ptr.add(4_usize);
-        data_ptr = step_simd::<D>(control4, data_ptr, decoded_ptr);
+        data_ptr = step_simd(decoder, control4, data_ptr, decoded_ptr);
         decoded_ptr = decoded_ptr.add(4_usize);
     }
-    // println!("Done big steps");
-    // println!(
-    //     "n={:?}, ctrl={:?}, data={:?}, end={:?}, out={:?}",
-    //     num_controls, control_ptr, data_ptr, end_ptr, decoded_ptr,
-    // );
-    while num_controls > 0 {
-        let control = *control_ptr;
-        control_ptr = control_ptr.add(1);
-        num_controls -= 1;
-
-        if data_ptr.add(16) > end_ptr {
-            return (data_ptr, false);
-        }
-        data_ptr = step_simd::<D>(control, data_ptr, decoded_ptr);
+    while done < num_controls && end_ptr.offset_from(data_ptr) >= 16 {
+        data_ptr = step_simd(decoder, *control_ptr.add(done), data_ptr, decoded_ptr);
         decoded_ptr = decoded_ptr.add(4_usize);
+        done += 1;
+    }
+
+    (data_ptr, done)
+}
+
+/// Like [decode_parts_into_simd], for the 0124 layout of [crate::zero124].
+/// Returns the number of data bytes used.
+pub(crate) fn decode_0124_parts_into(
+    len: usize,
+    control: &[u8],
+    data: &[u8],
+    output: &mut Vec<u32>,
+) -> Result<usize, StreamVbyteError> {
+    if control.len() < control_bytes_len(len) {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
     }
+    output.reserve(len);
+    let end: *const u8 = data.as_ptr_range().end;
+    let mut data_ptr: *const u8 = data.as_ptr();
+    // Full control bytes, as long as 16 data bytes can be read. A control byte
+    // of zeros uses no data bytes at all, so this is checked every time.
+    let mut done = 0;
+    let used = unsafe {
+        let mut decoded_ptr: *mut u32 = output.as_mut_ptr().add(output.len());
+        while done < len / 4 && end.offset_from(data_ptr) >= 16 {
+            data_ptr = step_simd_with_tables(
+                &mut NoDecode,
+                &ZERO124_SHUFFLE_TABLE,
+                &ZERO124_LENGTH_TABLE,
+                control[done],
+                data_ptr,
+                decoded_ptr,
+            );
+            decoded_ptr = decoded_ptr.add(4);
+            done += 1;
+        }
+        output.set_len(output.len() + 4 * done);
+        data_ptr.offset_from(data.as_ptr()) as usize
+    };
+    let rest = crate::zero124::decode_parts_into_scalar(
+        len - 4 * done,
+        &control[done..],
+        &data[used..],
+        output,
+    )?;
+    Ok(used + rest)
+}
 
-    (data_ptr, true)
+#[inline]
+pub(crate) unsafe fn step_simd<D: Decoder>(
+    decoder: &mut D,
+    control: u8,
+    data_ptr: *const u8,
+    decoded_ptr: *mut u32,
+) -> *const u8 {
+    step_simd_with_tables(
+        decoder,
+        &DECODE_SHUFFLE_TABLE,
+        &LENGTH_TABLE,
+        control,
+        data_ptr,
+        decoded_ptr,
+    )
 }
 
-unsafe fn step_simd<D: Decoder>(
+// Like `step_simd`, for a layout with other data lengths per 2-bit code.
+#[inline]
+unsafe fn step_simd_with_tables<D: Decoder>(
+    decoder: &mut D,
+    shuffle_table: &[[u8; 16]; 256],
+    length_table: &[u8; 256],
     control: u8,
     data_ptr: *const u8,
     decoded_ptr: *mut u32,
@@ -189,11 +319,11 @@ unsafe fn step_simd<D: Decoder>(
     // Safety: Safe if source data has 12 extra bytes allocated (we always
     // consume at least 4 bytes).
     let encoded: __m128i = _mm_loadu_si128(data_ptr as *const __m128i);
-    let entry: *const [u8; 16] = &DECODE_SHUFFLE_TABLE[control as usize] as *const _;
+    let entry: *const [u8; 16] = &shuffle_table[control as usize] as *const _;
     // Safety: the types are compatible and we allow unaligned reads.
     let mask = _mm_loadu_si128(entry as *const __m128i);
-    let decoded = D::simd_decode_4x32(_mm_shuffle_epi8(encoded, mask));
-    let bytes_consumed: u8 = LENGTH_TABLE[control as usize];
+    let decoded = decoder.simd_decode_4x32(_mm_shuffle_epi8(encoded, mask));
+    let bytes_consumed: u8 = length_table[control as usize];
     let data_ptr = data_ptr.add(bytes_consumed as usize);
     // Safety: we allocated enough memory.
     _mm_storeu_si128(decoded_ptr as *mut __m128i, decoded);
@@ -209,7 +339,7 @@ pub fn zigzag_decode_1(x: u32) -> i32 {
 #[cfg(target_feature = "sse2")]
 #[inline]
 pub unsafe fn zigzag_decode_4x32(data: __m128i) -> __m128i {
-    use std::arch::x86_64::{
+    use crate::x86_64::arch::{
         _mm_and_si128, _mm_set1_epi32, _mm_setzero_si128, _mm_srli_epi32, _mm_sub_epi32,
         _mm_xor_si128,
     };
@@ -223,11 +353,11 @@ pub unsafe fn zigzag_decode_4x32(data: __m128i) -> __m128i {
 }
 
 #[cfg(target_feature = "avx2")]
-use std::arch::x86_64::__m256i;
+use crate::x86_64::arch::__m256i;
 
 #[cfg(target_feature = "avx2")]
 pub unsafe fn zigzag_decode_8x32(data: __m256i) -> __m256i {
-    use std::arch::x86_64::{
+    use crate::x86_64::arch::{
         _mm256_and_si256, _mm256_set1_epi32, _mm256_setzero_si256, _mm256_srli_epi32,
         _mm256_sub_epi32, _mm256_xor_si256,
     };
@@ -298,6 +428,48 @@ mod tests {
         }
     }
 
+    // The unrolled loop stops early when fewer than 64 data bytes are left.
+    // The remaining control bytes must still line up with the data.
+    #[test]
+    fn stops_early_near_end() {
+        for n in 0..200 {
+            let input: Vec<u32> = (0..n).map(|x: u32| x * 37 % 600).collect();
+            let (len, bytes) = encode(&input);
+            assert_eq!(
+                decode_simd::<NoDecode>(len, &bytes).unwrap(),
+                input,
+                "{}",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn delta_and_frame_of_reference() {
+        for n in [0, 1, 5, 16, 17, 100, 1003] {
+            let input = crate::common::sample_values(n);
+            let (len, bytes) = encode(&input);
+            let (control, data) = bytes.split_at(control_bytes_len(len));
+
+            let mut output = vec![42];
+            let mut decoder = DeltaDecode::new(ZigZagDecode, 7);
+            decode_parts_into_with(&mut decoder, len, control, data, &mut output).unwrap();
+            let mut prev = 7u32;
+            let expected = input.iter().map(|&x| {
+                prev = prev.wrapping_add(ZigZagDecode.decode_1(x));
+                prev
+            });
+            assert!(output[1..].iter().copied().eq(expected), "{}", n);
+            assert_eq!(output[0], 42);
+
+            let mut output = Vec::new();
+            let mut decoder = FrameOfReferenceDecode::new(u32::MAX - 3);
+            decode_parts_into_with(&mut decoder, len, control, data, &mut output).unwrap();
+            let expected = input.iter().map(|&x| x.wrapping_add(u32::MAX - 3));
+            assert!(output.iter().copied().eq(expected), "{}", n);
+        }
+    }
+
     #[test]
     fn wrong_len() {
         let inputs = &[
@@ -40,12 +40,14 @@ impl Encoder for ZigZagEncode {
     }
 }
 
+#[inline]
 pub(crate) fn encode_simd<E: Encoder>(input: &[u32]) -> (usize, Vec<u8>) {
     let mut output = Vec::new();
     let items = encode_into_simd::<E>(input, &mut output);
     (items, output)
 }
 
+#[inline]
 pub(crate) fn encode_into_simd<E: Encoder>(input: &[u32], output: &mut Vec<u8>) -> usize {
     let items = input.len();
     if items == 0 {
@@ -63,8 +65,7 @@ pub(crate) fn encode_into_simd<E: Encoder>(input: &[u32], output: &mut Vec<u8>)
 
     unsafe {
         let data = encode_worker::<E>(items, input, controls, data);
-        let len = data.offset_from(output.as_ptr()) as usize;
-        let new_len = output.len() + len;
+        let new_len = data.offset_from(output.as_ptr()) as usize;
         debug_assert!(new_len <= output.capacity());
         output.set_len(new_len)
     };
@@ -72,21 +73,61 @@ pub(crate) fn encode_into_simd<E: Encoder>(input: &[u32], output: &mut Vec<u8>)
     items
 }
 
-use std::arch::x86_64::{
+use crate::x86_64::arch::{
     __m128i, _mm_add_epi32, _mm_adds_epu16, _mm_loadu_si128, _mm_min_epi16, _mm_min_epu8,
     _mm_movemask_epi8, _mm_packus_epi16, _mm_set1_epi16, _mm_set1_epi8, _mm_shuffle_epi8,
     _mm_srai_epi32, _mm_storeu_si128, _mm_xor_si128,
 };
 
-unsafe fn encode_worker<E: Encoder>(
+/// Compute the two control bytes for 8 values (already transformed), given as
+/// two vectors of 4 values each. The first control byte is in the low 8 bits.
+#[inline]
+pub(crate) unsafe fn control_keys_8x32(r0: __m128i, r1: __m128i) -> usize {
+    let mask_01: __m128i = _mm_set1_epi8(0x01);
+    let mask_7f00: __m128i = _mm_set1_epi16(0x7f00);
+
+    // Turn all non-zero bytes into 1, the rest stay at 0
+    let r2 = _mm_min_epu8(mask_01, r0);
+    let r3 = _mm_min_epu8(mask_01, r1);
+    // Ex: r2 = 01_00_00_00__01_01_00_00__01_01_01_01__01_01_01_00
+    //     r3 = 01_01_00_00__01_01_01_00__01_00_00_01__01_00_00_00
+    // Seen as u16x8:
+    //     r2 = 0001__0000___0101__0000___0101__0101___0101__0001
+    //     r3 = 0101__0000___0101__0001___0001__0100___0001__0000
+
+    // Takes [r2,r3] as u16x16 and turns 0101 => ff, the rest stays
+    let r2 = _mm_packus_epi16(r2, r3);
+    // Ex: r2 = 01_00_ff_00__ff_ff_ff_01__ff_00_ff_01__01_ff_01_00
+    // Reinterpreted as u16x8 for next op
+    //     r2 = 0001__00ff___ffff__01ff___00ff__01ff___ff01__0001
+
+    // Turn any 01ff into 0101
+    let r2 = _mm_min_epi16(r2, mask_01);
+    // Ex: r2 = 0001__00ff___ffff__0101___00ff__0101___ff01__0001
+
+    // converts: 0x0101 to 0x8001, 0xff01 to 0xffff
+    let r2 = _mm_adds_epu16(r2, mask_7f00);
+    // Ex: r2 = 7f01__7fff___ffff__8001___7fff__8001___ffff__7f01
+    // Back as bytes:
+    //     r2 = 01_7f_ff_7f__ff_ff_01_80__ff_7f_01_80__ff_ff_01_7f
+
+    // Takes the highest bit from each byte.
+    let keys = _mm_movemask_epi8(r2) as usize;
+    //      keys = 0b_00_11_10_01__10_11_01_00
+    // Rightmost bit corresponds to first byte.
+    //
+    // Or in order of input values:
+    // (from r0) 00_01_11_10  (from r1) 01_10_11_00
+    keys
+}
+
+#[inline]
+pub(crate) unsafe fn encode_worker<E: Encoder>(
     items: usize,
     mut input: *const u32,
     mut controls: *mut u8,
     mut data: *mut u8,
 ) -> *mut u8 {
-    let mask_01: __m128i = _mm_set1_epi8(0x01);
-    let mask_7f00: __m128i = _mm_set1_epi16(0x7f00);
-
     // Based on https://github.com/lemire/streamvbyte/blob/master/src/streamvbyte_x64_encode.c
     // That implementation in turn was contributed by aqrit
 
@@ -100,54 +141,7 @@ unsafe fn encode_worker<E: Encoder>(
         // Ex: r0 = 11_00_00_00__22_33_00_00__44_55_66_77__88_99_aa_00
         //     r1 = 10_20_00_00__30_40_50_00__60_00_00_90__a0_00_00_00_
 
-        // Turn all non-zero bytes into 1, the rest stay at 0
-        let r2 = _mm_min_epu8(mask_01, r0);
-        let r3 = _mm_min_epu8(mask_01, r1);
-        // println!("_min_epu8");
-        // debug_u8x16(r2);
-        // debug_u8x16(r3);
-        // debug_u16x8(r2);
-        // debug_u16x8(r3);
-        // Ex: r2 = 01_00_00_00__01_01_00_00__01_01_01_01__01_01_01_00
-        //     r3 = 01_01_00_00__01_01_01_00__01_00_00_01__01_00_00_00
-        // Seen as u16x8:
-        //     r2 = 0001__0000___0101__0000___0101__0101___0101__0001
-        //     r3 = 0101__0000___0101__0001___0001__0100___0001__0000
-
-        // Takes [r2,r3] as u16x16 and turns 0101 => ff, the rest stays
-        let r2 = _mm_packus_epi16(r2, r3);
-        // println!("_packus_epi16");
-        // debug_u16x8(r2);
-        // debug_u8x16(r2);
-        // Ex: r2 = 01_00_ff_00__ff_ff_ff_01__ff_00_ff_01__01_ff_01_00
-        // Reinterpreted as u16x8 for next op
-        //     r2 = 0001__00ff___ffff__01ff___00ff__01ff___ff01__0001
-
-        // Turn any 01ff into 0101
-        let r2 = _mm_min_epi16(r2, mask_01);
-        // println!("_min_epi16");
-        // Ex: r2 = 0001__00ff___ffff__0101___00ff__0101___ff01__0001
-        // debug_u16x8(r2);
-        // debug_u8x16(r2);
-
-        // converts: 0x0101 to 0x8001, 0xff01 to 0xffff
-        let r2 = _mm_adds_epu16(r2, mask_7f00);
-        // Ex: r2 = 7f01__7fff___ffff__8001___7fff__8001___ffff__7f01
-        // Back as bytes:
-        //     r2 = 01_7f_ff_7f__ff_ff_01_80__ff_7f_01_80__ff_ff_01_7f
-        // println!("_mm_adds_epu16");
-        // debug_u16x8(r2);
-        // debug_u8x16(r2);
-
-        // Takes the highest bit from each byte.
-        let keys = _mm_movemask_epi8(r2) as usize;
-        //      keys = 0b_00_11_10_01__10_11_01_00
-        // Rightmost bit corresponds to first byte.
-        //
-        // Or in order of input values:
-        // (from r0) 00_01_11_10  (from r1) 01_10_11_00
-        // println!("_movemask_epi8");
-        // println!("{:#b}", keys);
+        let keys = control_keys_8x32(r0, r1);
 
         let r2 = _mm_loadu_si128(
             (ENCODING_SHUFFLE_TABLE.as_ptr() as *const u8).add((keys << 4) & 0x03F0)
@@ -211,7 +205,7 @@ pub unsafe fn zigzag_encode_4x32(data: __m128i) -> __m128i {
 
 #[cfg(target_feature = "avx2")]
 pub unsafe fn zigzag_encode_8x32(data: __m256i) -> __m256i {
-    use std::arch::x86_64::{_mm256_add_epi32, _mm256_srai_epi32, _mm256_xor_si256};
+    use crate::x86_64::arch::{_mm256_add_epi32, _mm256_srai_epi32, _mm256_xor_si256};
     let data_shl_1 = _mm256_add_epi32(data, data);
     let data_shr_31 = _mm256_srai_epi32::<32>(data);
     _mm256_xor_si256(data_shl_1, data_shr_31)
@@ -375,4 +369,13 @@ mod tests {
             assert_eq!(&input, &decoded);
         }
     }
+
+    #[test]
+    fn encode_into_appends() {
+        let input = random_any_bit(37);
+        let mut output = vec![0xaa; 5];
+        assert_eq!(super::encode_into_simd::<NoEncode>(&input, &mut output), 37);
+        assert_eq!(output[..5], [0xaa; 5]);
+        assert_eq!(output[5..], crate::safe::encode(&input).1[..]);
+    }
 }
@@ -0,0 +1,40 @@
+use crate::x86_64::arch::{
+    __m128i, _mm_castsi128_ps, _mm_cmpeq_epi32, _mm_loadu_si128, _mm_movemask_ps, _mm_or_si128,
+    _mm_shuffle_epi32,
+};
+
+/// Intersect two strictly increasing slices, 4 elements of each at a time.
+///
+/// Compares each group of 4 values of `a` against all rotations of a group of
+/// 4 values of `b`, then advances the group(s) with the smaller maximum. Stops
+/// when fewer than 4 elements are left in either slice and returns the number
+/// of consumed elements of `a` and `b`. The caller finishes the rest.
+pub(crate) fn intersect_4x4(a: &[u32], b: &[u32], output: &mut Vec<u32>) -> (usize, usize) {
+    let (mut i, mut j) = (0, 0);
+    while i + 4 <= a.len() && j + 4 <= b.len() {
+        // Safety: we checked that 4 elements are available in both slices.
+        let mut mask = unsafe {
+            let va = _mm_loadu_si128(a.as_ptr().add(i) as *const __m128i);
+            let vb = _mm_loadu_si128(b.as_ptr().add(j) as *const __m128i);
+            let r0 = _mm_cmpeq_epi32(va, vb);
+            let r1 = _mm_cmpeq_epi32(va, _mm_shuffle_epi32::<0b00_11_10_01>(vb));
+            let r2 = _mm_cmpeq_epi32(va, _mm_shuffle_epi32::<0b01_00_11_10>(vb));
+            let r3 = _mm_cmpeq_epi32(va, _mm_shuffle_epi32::<0b10_01_00_11>(vb));
+            let any = _mm_or_si128(_mm_or_si128(r0, r1), _mm_or_si128(r2, r3));
+            _mm_movemask_ps(_mm_castsi128_ps(any))
+        };
+        while mask != 0 {
+            output.push(a[i + mask.trailing_zeros() as usize]);
+            mask &= mask - 1;
+        }
+        let a_max = a[i + 3];
+        let b_max = b[j + 3];
+        if a_max <= b_max {
+            i += 4;
+        }
+        if b_max <= a_max {
+            j += 4;
+        }
+    }
+    (i, j)
+}
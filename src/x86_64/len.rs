@@ -0,0 +1,52 @@
+use crate::x86_64::arch::{
+    __m128i, _mm_alignr_epi8, _mm_loadu_si128, _mm_set1_epi32, _mm_setzero_si128, _mm_sub_epi32,
+};
+
+use super::encode::{control_keys_8x32, Encoder};
+use crate::tables::len::LENGTH_TABLE;
+
+// All functions in here process the input 8 values at a time and return the
+// number of values processed together with their encoded data length. The
+// caller is responsible for the remaining `input.len() % 8` values.
+
+pub(crate) fn data_len_simd<E: Encoder>(input: &[u32]) -> (usize, usize) {
+    unsafe { data_len_worker(input, |data| E::simd_encode_4x32(data)) }
+}
+
+pub(crate) fn delta_data_len_simd(input: &[u32]) -> (usize, usize) {
+    unsafe {
+        let mut prev = _mm_setzero_si128();
+        data_len_worker(input, |data| {
+            // [prev[3], data[0], data[1], data[2]]
+            let shifted = _mm_alignr_epi8::<12>(data, prev);
+            prev = data;
+            _mm_sub_epi32(data, shifted)
+        })
+    }
+}
+
+pub(crate) fn reference_data_len_simd(input: &[u32], reference: u32) -> (usize, usize) {
+    unsafe {
+        let reference = _mm_set1_epi32(reference as i32);
+        data_len_worker(input, |data| _mm_sub_epi32(data, reference))
+    }
+}
+
+#[inline]
+unsafe fn data_len_worker<F>(input: &[u32], mut transform: F) -> (usize, usize)
+where
+    F: FnMut(__m128i) -> __m128i,
+{
+    let items = input.len() & !7;
+    let mut ptr: *const u32 = input.as_ptr();
+    let end: *const u32 = ptr.add(items);
+    let mut len = 0;
+    while ptr != end {
+        let r0 = transform(_mm_loadu_si128(ptr as *const __m128i));
+        let r1 = transform(_mm_loadu_si128(ptr.add(4) as *const __m128i));
+        let keys = control_keys_8x32(r0, r1);
+        len += LENGTH_TABLE[keys & 0xff] as usize + LENGTH_TABLE[keys >> 8] as usize;
+        ptr = ptr.add(8);
+    }
+    (items, len)
+}
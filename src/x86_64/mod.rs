@@ -1,2 +1,15 @@
+// Without SSSE3 at compile time, only the runtime-detected paths in
+// `crate::backend` use this module.
+#![cfg_attr(not(target_feature = "ssse3"), allow(dead_code))]
+
+// The intrinsics are the same on 32-bit x86, only the module differs.
+#[cfg(target_arch = "x86")]
+pub(crate) use std::arch::x86 as arch;
+#[cfg(target_arch = "x86_64")]
+pub(crate) use std::arch::x86_64 as arch;
+
 pub mod decode;
 pub mod encode;
+pub(crate) mod intersect;
+pub(crate) mod len;
+pub(crate) mod varint;
@@ -0,0 +1,128 @@
+use crate::x86_64::arch::{
+    __m128i, _mm_and_si128, _mm_loadu_si128, _mm_movemask_epi8, _mm_or_si128, _mm_set1_epi32,
+    _mm_shuffle_epi8, _mm_srli_epi32, _mm_storeu_si128,
+};
+
+use crate::{
+    common::StreamVbyteError,
+    tables::varint::{VARINT_SHUFFLE_TABLE, VARINT_STEP_TABLE},
+    varint::read_varint,
+    x86_64::decode::Decoder,
+};
+
+/// Decode `len` LEB128 varints into `u32` values, see [crate::varint::decode].
+///
+/// A step transforms four values but may keep fewer of them, so `D` must not
+/// carry state from one value to the next.
+#[inline]
+pub(crate) fn decode_simd<D: Decoder + Default>(
+    len: usize,
+    input: &[u8],
+) -> Result<Vec<u32>, StreamVbyteError> {
+    // Every varint takes at least one byte. Checked before reserving, so a
+    // bogus `len` cannot trigger a huge allocation.
+    if len > input.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    let mut decoder = D::default();
+    let mut output: Vec<u32> = Vec::with_capacity(len);
+    let out: *mut u32 = output.as_mut_ptr();
+    let mut offset = 0;
+    let mut done = 0;
+
+    // A step reads 16 bytes and writes 4 values, of which it keeps between 0
+    // and 4. Steps without a value, i.e. varints longer than 4 bytes, are
+    // decoded by the scalar code.
+    while len - done >= 4 && input.len() - offset >= 16 {
+        // Safety: 16 bytes are readable at `offset`, and `done + 4 <= len`
+        // values fit into `output`.
+        let (count, consumed) =
+            unsafe { step_simd(&mut decoder, input.as_ptr().add(offset), out.add(done)) };
+        if count == 0 {
+            let value = read_varint(input, &mut offset, 32)? as u32;
+            // Safety: `done < len`.
+            unsafe { *out.add(done) = decoder.decode_1(value) };
+            done += 1;
+        } else {
+            offset += consumed;
+            done += count;
+        }
+    }
+    while done < len {
+        let value = read_varint(input, &mut offset, 32)? as u32;
+        // Safety: `done < len`.
+        unsafe { *out.add(done) = decoder.decode_1(value) };
+        done += 1;
+    }
+    // Safety: all `len` values were written above.
+    unsafe { output.set_len(len) };
+    Ok(output)
+}
+
+// Decodes the varints that lie completely within the first 8 bytes at
+// `data_ptr`, see `tables::varint`. Returns the number of values and bytes
+// consumed.
+#[inline]
+unsafe fn step_simd<D: Decoder>(
+    decoder: &mut D,
+    data_ptr: *const u8,
+    decoded_ptr: *mut u32,
+) -> (usize, usize) {
+    let encoded: __m128i = _mm_loadu_si128(data_ptr as *const __m128i);
+    let continuation = (_mm_movemask_epi8(encoded) & 0xff) as usize;
+    let (count, consumed) = VARINT_STEP_TABLE[continuation];
+    let entry: *const [u8; 16] = &VARINT_SHUFFLE_TABLE[continuation] as *const _;
+    // Safety: the types are compatible and we allow unaligned reads.
+    let mask = _mm_loadu_si128(entry as *const __m128i);
+    // Every u32 now holds up to four 7-bit groups, one per byte.
+    let groups = _mm_and_si128(_mm_shuffle_epi8(encoded, mask), _mm_set1_epi32(0x7f7f7f7f));
+    // Join neighbouring groups into 14 bits per 16-bit half, then the halves.
+    let pairs = _mm_or_si128(
+        _mm_and_si128(groups, _mm_set1_epi32(0x007f007f)),
+        _mm_srli_epi32::<1>(_mm_and_si128(groups, _mm_set1_epi32(0x7f007f00))),
+    );
+    let decoded = _mm_or_si128(
+        _mm_and_si128(pairs, _mm_set1_epi32(0x3fff)),
+        _mm_srli_epi32::<2>(_mm_and_si128(pairs, _mm_set1_epi32(0x3fff0000))),
+    );
+    // Safety: the caller has room for 4 values.
+    _mm_storeu_si128(
+        decoded_ptr as *mut __m128i,
+        decoder.simd_decode_4x32(decoded),
+    );
+    (count as usize, consumed as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::ScalarDecoder;
+    use crate::x86_64::decode::{NoDecode, ZigZagDecode};
+
+    #[test]
+    fn step() {
+        // 1, 300, 2, 70000
+        let mut data = vec![1, 0xac, 0x02, 0x02, 0xf0, 0xa2, 0x04];
+        data.resize(16, 0);
+        let mut out = vec![0; 4];
+        let (count, consumed) =
+            unsafe { step_simd(&mut NoDecode, data.as_ptr(), out.as_mut_ptr()) };
+        assert_eq!((count, consumed), (4, 7));
+        assert_eq!(out, vec![1, 300, 2, 70000]);
+    }
+
+    #[test]
+    fn matches_scalar() {
+        for n in 0..200 {
+            // Mixes varints of all lengths, including 5 bytes.
+            let input = crate::common::sample_values(n);
+            let (len, bytes) = crate::varint::encode(&input);
+            assert_eq!(decode_simd::<NoDecode>(len, &bytes).unwrap(), input);
+            let zigzag: Vec<u32> = input.iter().map(|&x| ZigZagDecode.decode_1(x)).collect();
+            assert_eq!(decode_simd::<ZigZagDecode>(len, &bytes).unwrap(), zigzag);
+            if len > 0 {
+                assert!(decode_simd::<NoDecode>(len, &bytes[..bytes.len() - 1]).is_err());
+            }
+        }
+    }
+}
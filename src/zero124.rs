@@ -0,0 +1,185 @@
+//! The "0124" variant of StreamVByte.
+//!
+//! Uses the same layout as the default encoding, but the 2-bit codes mean 0,
+//! 1, 2 or 4 data bytes. Zero values take up no data bytes at all, which pays
+//! off for sparse inputs, at the cost of 3-byte values taking up 4 bytes.
+//! Decoding uses SIMD where available, like [crate::decode].
+//!
+//! Compatible with `streamvbyte_encode_0124`/`streamvbyte_decode_0124` from
+//! the reference C implementation.
+use crate::common::{control_bytes_len, max_compressed_len, StreamVbyteError};
+
+/// Encode a slice of `u32` values using the 0124 variant.
+///
+/// ```
+/// let (len, bytes) = streamvb::zero124::encode(&[0, 1, 300, 70000]);
+/// assert_eq!(bytes, vec![0b11_10_01_00, 1, 44, 1, 0x70, 0x11, 0x01, 0x00]);
+/// assert_eq!(streamvb::zero124::decode(len, &bytes).unwrap(), vec![0, 1, 300, 70000]);
+/// ```
+pub fn encode(values: &[u32]) -> (usize, Vec<u8>) {
+    let mut output: Vec<u8> = Vec::new();
+    encode_into(values, &mut output);
+    (values.len(), output)
+}
+
+/// Encode `values` and append the bytes to `output`.
+pub(crate) fn encode_into(values: &[u32], output: &mut Vec<u8>) {
+    if values.is_empty() {
+        return;
+    }
+    output.reserve(max_compressed_len(values.len()));
+    let start = output.len();
+    output.resize(start + control_bytes_len(values.len()), 0);
+    for (i, &value) in values.iter().enumerate() {
+        let bytes = value.to_le_bytes();
+        let code = if value == 0 {
+            0
+        } else if value < (1 << 8) {
+            output.push(bytes[0]);
+            1
+        } else if value < (1 << 16) {
+            output.extend_from_slice(&bytes[..2]);
+            2
+        } else {
+            output.extend_from_slice(&bytes);
+            3
+        };
+        output[start + i / 4] |= code << (2 * (i % 4));
+    }
+}
+
+/// Decode bytes encoded using [encode] into the original `u32` values.
+///
+/// Returns an error if the decoding process tried to read bytes outside of the
+/// input slice.
+pub fn decode(len: usize, input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
+    let mut result = Vec::new();
+    decode_into(len, input, &mut result)?;
+    Ok(result)
+}
+
+/// Decode `len` values, append them to `output` and return the number of
+/// input bytes used. `input` may extend past the encoded values.
+pub(crate) fn decode_into(
+    len: usize,
+    input: &[u8],
+    output: &mut Vec<u32>,
+) -> Result<usize, StreamVbyteError> {
+    let num_control_bytes = control_bytes_len(len);
+    // All-zero inputs have no data bytes.
+    if num_control_bytes > input.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    let (control, data) = input.split_at(num_control_bytes);
+    Ok(num_control_bytes + decode_parts_into(len, control, data, output)?)
+}
+
+// Decodes `len` values from separate control and data streams, appends them
+// to `output` and returns the number of data bytes used, using SIMD if
+// available.
+#[allow(clippy::needless_return)]
+fn decode_parts_into(
+    len: usize,
+    control: &[u8],
+    data: &[u8],
+    output: &mut Vec<u32>,
+) -> Result<usize, StreamVbyteError> {
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "ssse3",
+        not(feature = "safe-only")
+    ))]
+    {
+        return crate::x86_64::decode::decode_0124_parts_into(len, control, data, output);
+    }
+
+    #[cfg(all(
+        target_arch = "aarch64",
+        feature = "aarch64-simd",
+        not(feature = "safe-only")
+    ))]
+    {
+        return crate::aarch64::decode::decode_0124_parts_into(len, control, data, output);
+    }
+
+    #[allow(unreachable_code)]
+    {
+        decode_parts_into_scalar(len, control, data, output)
+    }
+}
+
+/// Like `decode_parts_into`, without SIMD.
+pub(crate) fn decode_parts_into_scalar(
+    len: usize,
+    control: &[u8],
+    data: &[u8],
+    output: &mut Vec<u32>,
+) -> Result<usize, StreamVbyteError> {
+    output.reserve(len);
+    let mut offset = 0;
+    for i in 0..len {
+        let nbytes = match (control[i / 4] >> (2 * (i % 4))) & 0x3 {
+            0 => 0,
+            1 => 1,
+            2 => 2,
+            _ => 4,
+        };
+        let bytes = data
+            .get(offset..offset + nbytes)
+            .ok_or(StreamVbyteError::DecodeOutOfBounds)?;
+        let mut buf = [0u8; 4];
+        buf[..nbytes].copy_from_slice(bytes);
+        output.push(u32::from_le_bytes(buf));
+        offset += nbytes;
+    }
+    Ok(offset)
+}
+
+/// Number of input bytes used by `len` values, without decoding them.
+pub(crate) fn encoded_len(len: usize, input: &[u8]) -> Result<usize, StreamVbyteError> {
+    let num_control_bytes = control_bytes_len(len);
+    if num_control_bytes > input.len() {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    let control = &input[..num_control_bytes];
+    let data_len: usize = (0..len)
+        .map(|i| match (control[i / 4] >> (2 * (i % 4))) & 0x3 {
+            0 => 0,
+            1 => 1,
+            2 => 2,
+            _ => 4,
+        })
+        .sum();
+    if data_len > input.len() - num_control_bytes {
+        return Err(StreamVbyteError::DecodeOutOfBounds);
+    }
+    Ok(num_control_bytes + data_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode, encoded_len};
+
+    #[test]
+    fn encode_decode() {
+        let inputs: &[Vec<u32>] = &[
+            vec![],
+            vec![0],
+            vec![0, 0, 0, 0, 0],
+            vec![1, 288, 3, 123123, 83291, 0, 16621, 30],
+            vec![0xffffff, 0x1000000, u32::MAX],
+            crate::common::sample_values(1003),
+            (0..1003).map(|x| (x % 7 == 0) as u32 * x * 1000).collect(),
+        ];
+        for input in inputs {
+            let (len, bytes) = encode(input);
+            assert_eq!(&decode(len, &bytes).unwrap(), input);
+            assert!(decode(len + 4, &bytes).is_err());
+            assert_eq!(encoded_len(len, &bytes).unwrap(), bytes.len());
+            if !bytes.is_empty() {
+                assert!(encoded_len(len, &bytes[..bytes.len() - 1]).is_err());
+            }
+        }
+        assert_eq!(encode(&[0, 0, 0]), (3, vec![0]));
+    }
+}
@@ -0,0 +1,93 @@
+// Generates the golden vectors in this directory using the reference C
+// implementation from https://github.com/lemire/streamvbyte
+//
+//   cc -O2 -Iinclude gen_golden.c src/*.c -o gen_golden && ./gen_golden
+//
+// (run from a checkout of the reference implementation, then copy the `*.bin`
+// files here).
+//
+// Each file is a sequence of records, one for every length from 0 to
+// MAX_COUNT:
+//
+//   [u32 LE count][u32 LE number of encoded bytes][encoded bytes]
+//
+// The input values are not stored. They are produced by `generate` below,
+// which is mirrored by `golden_input` in `src/conformance.rs`.
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "streamvbyte.h"
+#include "streamvbyte_zigzag.h"
+#include "streamvbytedelta.h"
+
+static const uint32_t EDGES[] = {
+    0,        1,          255,        256,       65535,     65536,
+    16777215, 16777216,   2147483647, 2147483648, 4294967294, 4294967295,
+};
+#define NUM_EDGES (sizeof(EDGES) / sizeof(EDGES[0]))
+
+static uint32_t xorshift32(uint32_t *state) {
+  uint32_t x = *state;
+  x ^= x << 13;
+  x ^= x >> 17;
+  x ^= x << 5;
+  *state = x;
+  return x;
+}
+
+static void generate(uint32_t count, uint32_t *out) {
+  uint32_t state = 0x9e3779b9u ^ count;
+  for (uint32_t i = 0; i < count; i++) {
+    uint32_t x = xorshift32(&state);
+    switch (x & 7) {
+    case 0: out[i] = EDGES[(x >> 3) % NUM_EDGES]; break;
+    case 1: out[i] = x >> 24; break;
+    case 2: out[i] = x >> 16; break;
+    case 3: out[i] = x >> 8; break;
+    case 4: out[i] = x; break;
+    case 5: out[i] = 0; break;
+    case 6: out[i] = x >> 28; break;
+    default: out[i] = x >> 12; break;
+    }
+  }
+}
+
+static void write_u32(FILE *f, uint32_t x) {
+  uint8_t b[4] = {x & 0xff, (x >> 8) & 0xff, (x >> 16) & 0xff, x >> 24};
+  fwrite(b, 1, 4, f);
+}
+
+#define MAX_COUNT 1000
+
+int main(void) {
+  const char *names[] = {"plain.bin", "zigzag.bin", "delta.bin", "0124.bin"};
+  uint32_t in[MAX_COUNT];
+  uint32_t tmp[MAX_COUNT];
+  uint8_t out[MAX_COUNT * 5];
+  for (int mode = 0; mode < 4; mode++) {
+    FILE *f = fopen(names[mode], "wb");
+    if (!f) {
+      perror(names[mode]);
+      return 1;
+    }
+    for (uint32_t count = 0; count <= MAX_COUNT; count++) {
+      generate(count, in);
+      size_t n = 0;
+      switch (mode) {
+      case 0: n = streamvbyte_encode(in, count, out); break;
+      case 1:
+        zigzag_encode((const int32_t *)in, tmp, count);
+        n = streamvbyte_encode(tmp, count, out);
+        break;
+      case 2: n = streamvbyte_delta_encode(in, count, out, 0); break;
+      case 3: n = streamvbyte_encode_0124(in, count, out); break;
+      }
+      write_u32(f, count);
+      write_u32(f, (uint32_t)n);
+      fwrite(out, 1, n, f);
+    }
+    fclose(f);
+  }
+  return 0;
+}